@@ -0,0 +1,57 @@
+#ifndef ALIGN_H
+#define ALIGN_H
+
+/* This file is generated by cbindgen. Do not edit it. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// An alignment engine.
+typedef struct Align Align;
+
+// Creates an engine with the default options.
+//
+// The engine must be freed with `align_free`.
+struct Align *align_new(void);
+
+// Sets the positioning spec, e.g. `<><`.
+//
+// Returns 0 on success and -1 if the spec is invalid.
+//
+// # Safety
+//
+// `align` must have been created by `align_new` and `spec` must be a NUL-terminated
+// string.
+int align_set_spec(struct Align *align, const char *spec);
+
+// Aligns the columns of `text`.
+//
+// Returns the aligned text, or NULL on error. The text is owned by the engine and stays
+// valid until the next call of `align_process` or `align_free`.
+//
+// # Safety
+//
+// `align` must have been created by `align_new` and `text` must be a NUL-terminated
+// string.
+const char *align_process(struct Align *align, const char *text);
+
+// Returns the message of the last error.
+//
+// The message is owned by the engine and stays valid until the next error or
+// `align_free`.
+//
+// # Safety
+//
+// `align` must have been created by `align_new`.
+const char *align_last_error(const struct Align *align);
+
+// Frees an engine. Does nothing if `align` is NULL.
+//
+// # Safety
+//
+// `align` must be NULL or have been created by `align_new`.
+void align_free(struct Align *align);
+
+#endif  /* ALIGN_H */
@@ -0,0 +1,167 @@
+use {
+    crate::{find_sep, string_serde, trim, AlignConfig, AlignError},
+    serde::{Deserialize, Deserializer, Serialize, Serializer},
+    std::{
+        fmt::{self, Display, Formatter},
+        num::NonZeroUsize,
+    },
+};
+
+/// The occurrences of the `--on` token at which lines are split.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Occurrence {
+    /// The n-th occurrence, starting at 1.
+    Nth(NonZeroUsize),
+    /// Every occurrence.
+    All,
+}
+
+impl Display for Occurrence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Occurrence::Nth(n) => write!(f, "{n}"),
+            Occurrence::All => f.write_str("*"),
+        }
+    }
+}
+
+string_serde!(Occurrence, parse_occurrence);
+
+/// Parses an occurrence such as `2` or `*`.
+pub fn parse_occurrence(s: &str) -> Result<Occurrence, AlignError> {
+    if s == "*" {
+        return Ok(Occurrence::All);
+    }
+    s.parse()
+        .map(Occurrence::Nth)
+        .map_err(|_| AlignError::Value(format!("Expected a positive number or `*`, found `{s}`")))
+}
+
+/// Splits a line at the selected occurrences of `token` outside of strings and then the
+/// rest of the line at each of the `--then` tokens.
+///
+/// The tokens themselves become cells. Returns `None` if the line does not contain the
+/// selected occurrence of `token`.
+pub fn split(line: &[u8], token: &str, opts: &AlignConfig) -> Option<Vec<(usize, usize)>> {
+    let mut words = split_on(line, token, opts)?;
+    // The number of tokens that were missing since the last token that was found.
+    let mut missing = 0;
+    for token in &opts.then {
+        let (start, end) = *words.last().unwrap();
+        let rest = &line[start..end];
+        let Some(i) = find_sep(rest, opts.str_delim(words.len() - 1), |s| {
+            s.starts_with(token.as_bytes())
+        }) else {
+            missing += 1;
+            continue;
+        };
+        let at = start + i;
+        words.pop();
+        words.push(trim(line, start, at));
+        // Empty cells keep this token in its column.
+        for _ in 0..missing {
+            words.extend([(at, at), (at, at)]);
+        }
+        missing = 0;
+        words.push((at, at + token.len()));
+        words.push(trim(line, at + token.len(), end));
+    }
+    if words.last().is_some_and(|&(start, end)| start == end) {
+        words.pop();
+    }
+    Some(words)
+}
+
+fn split_on(line: &[u8], token: &str, opts: &AlignConfig) -> Option<Vec<(usize, usize)>> {
+    let token = token.as_bytes();
+    let mut words = Vec::new();
+    let mut start = 0;
+    let mut pos = 0;
+    let mut seen = 0;
+    while let Some(i) = find_sep(&line[pos..], opts.str_delim(words.len()), |s| {
+        s.starts_with(token)
+    }) {
+        let at = pos + i;
+        pos = at + token.len().max(1);
+        seen += 1;
+        if let Occurrence::Nth(n) = opts.occurrence {
+            if seen < n.get() {
+                continue;
+            }
+        }
+        words.push(trim(line, start, at));
+        words.push((at, at + token.len()));
+        start = at + token.len();
+        if opts.occurrence != Occurrence::All || pos > line.len() {
+            break;
+        }
+    }
+    if words.is_empty() {
+        return None;
+    }
+    words.push(trim(line, start, line.len()));
+    Some(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Splits the line and returns the cells.
+    fn cells<'a>(line: &'a str, token: &str, opts: &AlignConfig) -> Option<Vec<&'a str>> {
+        let words = split(line.as_bytes(), token, opts)?;
+        Some(words.iter().map(|&(s, e)| &line[s..e]).collect())
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(parse_occurrence("*").unwrap(), Occurrence::All);
+        assert_eq!(
+            parse_occurrence("2").unwrap(),
+            Occurrence::Nth(NonZeroUsize::new(2).unwrap())
+        );
+        assert!(parse_occurrence("0").is_err());
+        assert!(parse_occurrence("x").is_err());
+    }
+
+    #[test]
+    fn occurrences() {
+        let mut opts = AlignConfig::default();
+        let line = r#"a => "b => c" => d => e"#;
+        assert_eq!(
+            cells(line, "=>", &opts).unwrap(),
+            ["a", "=>", r#""b => c" => d => e"#]
+        );
+        opts.occurrence = parse_occurrence("2").unwrap();
+        assert_eq!(
+            cells(line, "=>", &opts).unwrap(),
+            [r#"a => "b => c""#, "=>", "d => e"]
+        );
+        opts.occurrence = Occurrence::All;
+        assert_eq!(
+            cells(line, "=>", &opts).unwrap(),
+            ["a", "=>", r#""b => c""#, "=>", "d", "=>", "e"]
+        );
+        opts.occurrence = parse_occurrence("4").unwrap();
+        assert_eq!(cells(line, "=>", &opts), None);
+        assert_eq!(cells("a b", "=>", &opts), None);
+    }
+
+    #[test]
+    fn then() {
+        let opts = AlignConfig {
+            then: vec![":".into(), "//".into()],
+            ..AlignConfig::default()
+        };
+        assert_eq!(
+            cells("a = b : c // d", "=", &opts).unwrap(),
+            ["a", "=", "b", ":", "c", "//", "d"]
+        );
+        // Missing tokens leave empty cells, so that later tokens stay in their columns.
+        assert_eq!(
+            cells("a = b // d", "=", &opts).unwrap(),
+            ["a", "=", "b", "", "", "//", "d"]
+        );
+        assert_eq!(cells("a = b", "=", &opts).unwrap(), ["a", "=", "b"]);
+    }
+}
@@ -0,0 +1,125 @@
+#[cfg(feature = "cli")]
+use crate::{line_width, AlignConfig};
+use std::borrow::Cow;
+
+pub const BOLD: &str = "\x1b[1m";
+pub const RESET: &str = "\x1b[0m";
+
+/// Removes the ANSI escape sequences from `s`.
+pub fn strip(s: &str) -> Cow<'_, str> {
+    if !s.contains('\x1b') {
+        return Cow::Borrowed(s);
+    }
+    let mut res = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            res.push(c);
+            continue;
+        }
+        // Control sequences end at the first character in the range @ to ~.
+        if chars.next() == Some('[') {
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        }
+    }
+    Cow::Owned(res)
+}
+
+/// The background of every other line with `--zebra`.
+#[cfg(feature = "cli")]
+const STRIPE: &str = "\x1b[48;5;236m";
+
+/// Returns the line without its line ending.
+#[cfg(feature = "cli")]
+fn content(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+/// Gives every other line a background that extends to the width of the widest line.
+#[cfg(feature = "cli")]
+pub fn zebra(opts: &AlignConfig, output: &[u8]) -> Vec<u8> {
+    let lines: Vec<&[u8]> = output.split_inclusive(|&c| c == b'\n').collect();
+    let width = lines
+        .iter()
+        .map(|l| line_width(opts, content(l)))
+        .max()
+        .unwrap_or(0);
+    let mut res = Vec::with_capacity(output.len());
+    for (i, line) in lines.into_iter().enumerate() {
+        if i % 2 == 0 {
+            res.extend_from_slice(line);
+            continue;
+        }
+        let text = content(line);
+        res.extend_from_slice(STRIPE.as_bytes());
+        // Keep the background after the resets of other styles.
+        let restyled = String::from_utf8_lossy(text).replace(RESET, &format!("{RESET}{STRIPE}"));
+        res.extend_from_slice(restyled.as_bytes());
+        res.resize(
+            res.len() + width.saturating_sub(line_width(opts, text)),
+            b' ',
+        );
+        res.extend_from_slice(RESET.as_bytes());
+        res.extend_from_slice(&line[text.len()..]);
+    }
+    res
+}
+
+/// Returns the part of each line between the display columns `first` and `last`,
+/// starting at 1.
+///
+/// Escape sequences are kept so that styles carry over into the window. Tabs are expanded
+/// and wide characters that are cut by the start of the window are replaced by spaces.
+#[cfg(feature = "cli")]
+pub fn crop(opts: &AlignConfig, output: &[u8], first: usize, last: usize) -> Vec<u8> {
+    let mut res = Vec::with_capacity(output.len());
+    for line in output.split_inclusive(|&c| c == b'\n') {
+        let text = content(line);
+        let text = String::from_utf8_lossy(text);
+        let mut out = String::new();
+        // The display column of the next character.
+        let mut col = 1;
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                out.push(c);
+                let Some(next) = chars.next() else {
+                    break;
+                };
+                out.push(next);
+                if next == '[' {
+                    for c in chars.by_ref() {
+                        out.push(c);
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                continue;
+            }
+            let (width, text) = match c {
+                '\t' => (8 - (col - 1) % 8, None),
+                _ => (opts.width(c.encode_utf8(&mut [0; 4])), Some(c)),
+            };
+            let end = col + width;
+            if col >= first && end - 1 <= last {
+                match text {
+                    Some(c) => out.push(c),
+                    None => out.push_str(&" ".repeat(width)),
+                }
+            } else if col < first && end > first {
+                let visible = (end - first).min(last + 1 - first);
+                out.push_str(&" ".repeat(visible));
+            }
+            col = end;
+        }
+        res.extend_from_slice(out.as_bytes());
+        res.extend_from_slice(&line[content(line).len()..]);
+    }
+    res
+}
@@ -0,0 +1,22 @@
+use {
+    crate::{align_stream, AlignConfig, AlignError, Diagnostic},
+    tokio::io::{AsyncBufRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+};
+
+/// Aligns the columns of the lines read from `input` and writes them to `output`.
+///
+/// This is the asynchronous version of [`align_stream`]. The alignment itself does not
+/// block, since all lines are read before they are aligned.
+pub async fn align_stream_async(
+    config: &AlignConfig,
+    mut input: impl AsyncBufRead + Unpin,
+    mut output: impl AsyncWrite + Unpin,
+) -> Result<Vec<Diagnostic>, AlignError> {
+    let mut text = Vec::new();
+    input.read_to_end(&mut text).await?;
+    let mut aligned = Vec::new();
+    let log = align_stream(config, &text[..], &mut aligned)?;
+    output.write_all(&aligned).await?;
+    output.flush().await?;
+    Ok(log)
+}
@@ -0,0 +1,497 @@
+use {
+    crate::{
+        ansi, clipboard, compute_groups, decompress, detect,
+        editorconfig::EditorConfig,
+        follow::{self, Widen},
+        infer, limits,
+        log::{info, warning},
+        mapped, meta, pager, parse_lines, parse_range, read_as_unicode, sample, state,
+        timings::Timings,
+        transform, write_inputs, AlignConfig, AlignError, Diagnostic, HeaderStyle, Input,
+        LineRange, Positioning, Text,
+    },
+    anyhow::{bail, Context, Error},
+    clap::{
+        builder::{styling::AnsiColor, Styles},
+        Parser, ValueEnum,
+    },
+    std::{
+        fmt::Display,
+        fs::File,
+        io::{stdin, stdout, BufRead, BufReader, IsTerminal, Write},
+        num::NonZeroUsize,
+        path::{Path, PathBuf},
+        thread,
+    },
+};
+
+fn styles() -> Styles {
+    Styles::styled()
+        .header(AnsiColor::Yellow.on_default())
+        .usage(AnsiColor::Yellow.on_default())
+        .literal(AnsiColor::Green.on_default())
+        .placeholder(AnsiColor::Green.on_default())
+}
+
+/// The command line arguments.
+///
+/// The options of the alignment are flattened into the arguments. The remaining arguments
+/// select the input and where the output goes.
+#[derive(Debug, Parser)]
+#[command(
+    about = "Reads text from files or stdin, aligns columns, and prints the result to stdout.",
+    long_about = None,
+    styles = styles()
+)]
+pub struct Cli {
+    #[command(flatten)]
+    pub config: AlignConfig,
+    /// The files to align.
+    ///
+    /// The file `-` is stdin. By default, the text is read from stdin. The output of each
+    /// file follows the indentation, line ending, and trailing whitespace settings of its
+    /// `.editorconfig`.
+    #[arg(value_name = "file")]
+    pub files: Vec<PathBuf>,
+    /// A file listing further files to align, one per line.
+    ///
+    /// With `-`, the list is read from stdin. With `-z`, the names are separated by NUL.
+    #[arg(long, value_name = "list")]
+    pub files_from: Option<PathBuf>,
+    /// Read the text from the system clipboard instead of stdin.
+    ///
+    /// This requires the `clipboard` feature.
+    #[arg(long, conflicts_with_all = ["files", "files_from", "follow", "sample"])]
+    pub clip_in: bool,
+    /// Write the output to the system clipboard instead of stdout.
+    ///
+    /// This requires the `clipboard` feature.
+    #[arg(long, conflicts_with_all = ["follow", "sample", "pager"])]
+    pub clip_out: bool,
+    /// Do not print warnings.
+    ///
+    /// By default, warnings are printed about lines whose indentation mixes tabs and
+    /// spaces or differs from the indentation of the first line, and about long lines.
+    #[arg(short = 'q', long, conflicts_with = "verbose")]
+    pub quiet: bool,
+    /// Describe what is done on stderr.
+    ///
+    /// With `-v`, the detected separator, the encoding and size of each input, and the
+    /// number of groups of columns are printed. With `-vv`, the number of lines and the
+    /// column widths of each group are printed as well.
+    #[arg(short = 'v', long, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+    /// Print the inferred types of the columns instead of the aligned text.
+    ///
+    /// Each line contains the number of a column, its type, and its header cell if
+    /// there is a header.
+    #[arg(long, requires = "infer")]
+    pub schema: bool,
+    /// Show the output in a pager if it does not fit on the screen.
+    ///
+    /// The pager is taken from the PAGER environment variable. By default, it is
+    /// `less -S`, which does not wrap long lines.
+    #[arg(long, conflicts_with = "follow")]
+    pub pager: bool,
+    /// Give every other line a background color.
+    ///
+    /// The background extends to the width of the widest line. Like the other colors,
+    /// the background is subject to --color.
+    #[arg(long, conflicts_with = "follow")]
+    pub zebra: bool,
+    /// When to write colors and styles, as for --color-if, --zebra, and bold headers.
+    ///
+    /// With auto, colors are only written if stdout is a terminal.
+    #[arg(long, value_name = "when", default_value = "auto")]
+    pub color: ColorChoice,
+    /// Print only the display columns of the output in this range.
+    ///
+    /// Either end of the range can be omitted. Columns are numbered starting at 1. Wide
+    /// characters that do not fit into the range entirely are removed or, at the start of
+    /// the range, replaced by spaces.
+    ///
+    /// Example: 40-120
+    #[arg(long, value_name = "range", value_parser = parse_column_range, conflicts_with_all = ["follow", "sample"])]
+    pub view: Option<LineRange>,
+    /// Make the columns at least as wide as the widths saved in this file.
+    ///
+    /// A missing file is ignored.
+    #[arg(long, value_name = "file")]
+    pub load_widths: Option<PathBuf>,
+    /// Save the column widths to this file.
+    ///
+    /// Together with --load-widths and the same file, the columns never become narrower
+    /// from one run to the next.
+    #[arg(long, value_name = "file")]
+    pub save_widths: Option<PathBuf>,
+    /// Write the layout of the output to this file as JSON.
+    ///
+    /// The layout contains the number of rows and, for each group of lines aligned
+    /// together, the width, alignment, and inferred type of each column.
+    #[arg(long, value_name = "file")]
+    pub emit_meta: Option<PathBuf>,
+    /// Compute the column widths from the first n lines only.
+    ///
+    /// The following lines are printed as soon as they have been read, and their cells
+    /// overflow the columns if they are wider. This starts the output of long streams
+    /// before all of the input has been read. The input is stdin or a single file. Each
+    /// later group of columns, such as a block with --blocks, gets the widths of its own
+    /// first n lines.
+    #[arg(
+        long,
+        value_name = "n",
+        conflicts_with_all = [
+            "follow", "lines", "skip", "encoding", "number", "totals", "pager", "zebra",
+            "detect", "yaml", "ini", "sql"
+        ]
+    )]
+    pub sample: Option<NonZeroUsize>,
+    /// Keep reading the file as it grows.
+    ///
+    /// Whenever no more input is available, the lines read so far are printed using the
+    /// column widths of all lines seen so far. The widening policy determines whether
+    /// columns may become wider once lines have been printed.
+    #[arg(long, value_name = "widen", num_args = 0..=1, default_missing_value = "grow")]
+    pub follow: Option<Widen>,
+    /// Print the time spent in each phase and the peak memory usage to stderr.
+    ///
+    /// The phases are reading the input, splitting it into cells, transforming the cells,
+    /// computing the column widths, rendering the output, and writing it. The peak memory
+    /// usage is only printed on Linux.
+    #[arg(long, conflicts_with_all = ["follow", "sample"])]
+    pub timings: bool,
+    /// The maximum number of files split into cells at the same time.
+    ///
+    /// By default, this is the number of available CPUs.
+    #[arg(long, value_name = "n")]
+    pub jobs: Option<NonZeroUsize>,
+    /// The memory that aligning the input may take up.
+    ///
+    /// If the input is estimated to need more, a single input is aligned as with `--sample
+    /// 1000` and multiple files are rejected. The suffixes K, M, G, and T stand for powers
+    /// of 1024. The size of stdin is not known in advance, so stdin is always read
+    /// completely.
+    ///
+    /// Example: 512M
+    #[arg(long, value_name = "size", value_parser = limits::parse_size)]
+    pub max_memory: Option<u64>,
+}
+
+/// When colors are written.
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum ColorChoice {
+    /// Write colors if stdout is a terminal.
+    Auto,
+    Always,
+    Never,
+}
+
+impl Cli {
+    /// Returns whether colors are written to stdout.
+    fn colors(&self) -> bool {
+        match self.color {
+            ColorChoice::Auto => stdout().is_terminal(),
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+
+    /// Returns the diagnostics selected by `--quiet` and `--verbose`.
+    pub(crate) fn verbosity(&self) -> Verbosity {
+        Verbosity {
+            quiet: self.quiet,
+            verbose: self.verbose,
+        }
+    }
+}
+
+/// The diagnostics that are printed.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Verbosity {
+    quiet: bool,
+    verbose: u8,
+}
+
+impl Verbosity {
+    /// Prints the diagnostics in `log` to stderr and clears it.
+    pub(crate) fn report(self, log: &mut Vec<Diagnostic>) {
+        for diagnostic in log.drain(..) {
+            let shown = match diagnostic {
+                Diagnostic::Warning(_) => !self.quiet,
+                Diagnostic::Info { level, .. } => self.verbose >= level,
+            };
+            if shown {
+                eprintln!("{diagnostic}");
+            }
+        }
+    }
+}
+
+fn parse_column_range(s: &str) -> Result<LineRange, AlignError> {
+    parse_range(s, "column")
+}
+
+/// Runs the command line interface with the given arguments.
+pub fn run(mut cli: Cli) -> Result<(), Error> {
+    if cli.config.stops.windows(2).any(|w| w[0] >= w[1]) {
+        return Err(AlignError::Stops.into());
+    }
+    if !cli.colors() {
+        cli.config.color_if.clear();
+        cli.config.header_style.retain(|&s| s != HeaderStyle::Bold);
+    }
+    if let Some(list) = &cli.files_from {
+        let files = read_file_list(list, &cli.config)?;
+        cli.files.extend(files);
+    }
+    if let Some(widen) = cli.follow {
+        return follow::follow(&mut cli, widen);
+    }
+    if let Some(n) = cli.sample {
+        return sample::sample(&mut cli, n.get());
+    }
+    let verbosity = cli.verbosity();
+    let mut log = Vec::new();
+    if let Some(max) = cli.max_memory {
+        let needed = limits::estimate(&cli.files);
+        if needed > max {
+            if cli.files.len() > 1 {
+                bail!("The files need about {needed} bytes of memory but only {max} are allowed");
+            }
+            let n = limits::STREAM_SAMPLE;
+            warning!(
+                log,
+                "the input needs too much memory, using the widths of {n} lines"
+            );
+            verbosity.report(&mut log);
+            return sample::sample(&mut cli, n);
+        }
+    }
+
+    if let Some(path) = &cli.load_widths {
+        for (i, width) in state::load_widths(path)?.into_iter().enumerate() {
+            let max_width = &mut cli.config.positioning.max_width;
+            max_width.set(i, max_width.get(i).max(width));
+        }
+    }
+    let mut timings = Timings::new();
+    let inputs = read_inputs(&mut cli, &mut timings, &mut log);
+    verbosity.report(&mut log);
+    let mut inputs = inputs?;
+    let opts = &mut cli.config;
+    transform(opts, &mut inputs)?;
+    timings.lap("transform");
+    if cli.schema {
+        let schema = infer::schema(opts, &inputs);
+        return Ok(stdout().lock().write_all(schema.as_bytes())?);
+    }
+    let groups = compute_groups(opts, &mut inputs);
+    timings.lap("widths");
+    info!(log, 1, "number of groups of columns: {}", groups.len());
+    for (group, positioning) in groups.iter().enumerate() {
+        let lines = inputs.iter().flat_map(|i| &i.lines);
+        let lines = lines.filter(|l| l.group == group).count();
+        let widths = positioning.max_width.as_slice();
+        info!(
+            log,
+            2,
+            "group {}: {lines} lines, widths {widths:?}",
+            group + 1
+        );
+    }
+    verbosity.report(&mut log);
+    if let Some(path) = &cli.save_widths {
+        state::save_widths(path, &groups)?;
+    }
+    if let Some(path) = &cli.emit_meta {
+        meta::emit(path, &cli.config, &inputs, &groups)?;
+    }
+    let res = print(&cli, &inputs, &groups, &mut timings);
+    if cli.timings {
+        timings.report();
+    }
+    res
+}
+
+/// Writes the aligned lines to stdout or wherever the arguments send them.
+fn print(
+    cli: &Cli,
+    inputs: &[Input],
+    groups: &[Positioning],
+    timings: &mut Timings,
+) -> Result<(), Error> {
+    let opts = &cli.config;
+    if cli.pager || cli.zebra || cli.view.is_some() || cli.clip_out || cli.timings {
+        let mut out = Vec::new();
+        write_inputs(opts, inputs, groups, &mut out)?;
+        if let Some(view) = cli.view {
+            out = ansi::crop(opts, &out, view.first, view.last);
+        }
+        if cli.clip_out {
+            return clipboard::write(&out);
+        }
+        if cli.zebra && cli.colors() {
+            out = ansi::zebra(opts, &out);
+        }
+        timings.lap("render");
+        if cli.pager {
+            pager::page(&out)?;
+        } else {
+            stdout().lock().write_all(&out)?;
+        }
+        timings.lap("write");
+        return Ok(());
+    }
+    Ok(write_inputs(opts, inputs, groups, &mut stdout().lock())?)
+}
+
+/// Reads the names of the files listed by `--files-from`.
+fn read_file_list(path: &Path, opts: &AlignConfig) -> Result<Vec<PathBuf>, Error> {
+    let list: Box<dyn BufRead> = match path.to_str() {
+        Some("-") => Box::new(stdin().lock()),
+        _ => Box::new(BufReader::new(
+            File::open(path).with_context(|| format!("Could not open {}", path.display()))?,
+        )),
+    };
+    let mut files = Vec::new();
+    for name in list.split(opts.record_delim()) {
+        let mut name = name.with_context(|| format!("Could not read {}", path.display()))?;
+        if !opts.null && name.last() == Some(&b'\r') {
+            name.pop();
+        }
+        if name.is_empty() {
+            continue;
+        }
+        let name = String::from_utf8(name)
+            .with_context(|| format!("{} contains an invalid file name", path.display()))?;
+        files.push(PathBuf::from(name));
+    }
+    Ok(files)
+}
+
+/// Decompresses the input of the file at `path`, or of stdin if `path` is `None`, as
+/// selected by `--decompress`.
+pub(crate) fn decompressed<'a>(
+    mut input: Box<dyn BufRead + 'a>,
+    path: Option<&Path>,
+    opts: &AlignConfig,
+) -> Result<Box<dyn BufRead + 'a>, Error> {
+    let format = decompress::select(opts.decompress, path, || input.fill_buf())?;
+    match format {
+        Some(format) => decompress::decoder(input, format),
+        None => Ok(input),
+    }
+}
+
+/// Reads stdin, decompressing it if requested.
+fn read_stdin(opts: &AlignConfig, log: &mut Vec<Diagnostic>) -> Result<Text, Error> {
+    let input = decompressed(Box::new(stdin().lock()), None, opts)?;
+    Ok(read_as_unicode(input, None, opts, log)?)
+}
+
+/// Reads a file, decompressing it if necessary.
+fn read_file(
+    path: &Path,
+    file: File,
+    opts: &AlignConfig,
+    log: &mut Vec<Diagnostic>,
+) -> Result<Text, Error> {
+    if let Some(text) = mapped::read(path, &file, opts) {
+        return Ok(text);
+    }
+    let input = decompressed(Box::new(BufReader::new(file)), Some(path), opts)
+        .with_context(|| format!("Could not read {}", path.display()))?;
+    Ok(read_as_unicode(input, Some(path), opts, log)?)
+}
+
+/// Reports the size and encoding of an input with `-v`.
+fn log_text(log: &mut Vec<Diagnostic>, name: &dyn Display, text: &Text) {
+    let encoding = text.encoding.name();
+    info!(
+        log,
+        1,
+        "read {} lines from {name} as {encoding}",
+        text.lines.len()
+    );
+}
+
+fn read_inputs(
+    cli: &mut Cli,
+    timings: &mut Timings,
+    log: &mut Vec<Diagnostic>,
+) -> Result<Vec<Input>, Error> {
+    let opts = &mut cli.config;
+    let mut texts = Vec::new();
+    if cli.clip_in {
+        let text = clipboard::read()?;
+        texts.push((
+            read_as_unicode(text.as_bytes(), None, opts, log)?,
+            EditorConfig::default(),
+        ));
+        log_text(log, &"the clipboard", &texts[0].0);
+    } else if cli.files.is_empty() {
+        texts.push((read_stdin(opts, log)?, EditorConfig::default()));
+        log_text(log, &"stdin", &texts[0].0);
+    }
+    for path in &cli.files {
+        if path.as_os_str() == "-" {
+            let text = read_stdin(opts, log)?;
+            log_text(log, &"stdin", &text);
+            texts.push((text, EditorConfig::default()));
+            continue;
+        }
+        let file =
+            File::open(path).with_context(|| format!("Could not open {}", path.display()))?;
+        let text = read_file(path, file, opts, log)?;
+        log_text(log, &path.display(), &text);
+        let config = EditorConfig::of(&decompress::strip_extension(path))?;
+        texts.push((text, config));
+    }
+    if opts.null {
+        for (_, config) in &mut texts {
+            config.set_eol(b"\0");
+        }
+    }
+    timings.lap("read");
+    if opts.detect {
+        let lines = texts.iter().flat_map(|t| &t.0.lines);
+        detect::detect_separator(opts, lines.map(|l| &**l), log);
+    }
+    let jobs = match cli.jobs {
+        Some(jobs) => jobs.get(),
+        None => thread::available_parallelism().map_or(1, |n| n.get()),
+    };
+    let opts = &*opts;
+    let mut inputs = Vec::with_capacity(texts.len());
+    while !texts.is_empty() {
+        let batch: Vec<_> = texts.drain(..jobs.min(texts.len())).collect();
+        thread::scope(|scope| {
+            let parsers: Vec<_> = batch
+                .into_iter()
+                .map(|(text, config)| {
+                    scope.spawn(move || {
+                        // Each file gets its own diagnostics, which are reported in order.
+                        let mut log = Vec::new();
+                        let (indent, lines) = parse_lines(opts, text.lines, &mut log)?;
+                        let input = Input {
+                            indent,
+                            lines,
+                            config,
+                            encoding: text.encoding,
+                            bom: text.bom,
+                        };
+                        Ok::<_, AlignError>((input, log))
+                    })
+                })
+                .collect();
+            for parser in parsers {
+                let (input, mut parsed) = parser.join().unwrap()?;
+                inputs.push(input);
+                log.append(&mut parsed);
+            }
+            Ok::<_, AlignError>(())
+        })?;
+    }
+    timings.lap("tokenize");
+    Ok(inputs)
+}
@@ -0,0 +1,35 @@
+#[cfg(not(feature = "clipboard"))]
+use anyhow::bail;
+#[cfg(feature = "clipboard")]
+use anyhow::Context;
+use anyhow::Error;
+
+/// Returns the text in the system clipboard.
+#[cfg(feature = "clipboard")]
+pub fn read() -> Result<String, Error> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .context("Could not read the clipboard")
+}
+
+/// Replaces the contents of the system clipboard by the text.
+///
+/// On X11, the text is only available while the process is running unless a clipboard
+/// manager takes it over.
+#[cfg(feature = "clipboard")]
+pub fn write(text: &[u8]) -> Result<(), Error> {
+    let text = String::from_utf8_lossy(text);
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(text))
+        .context("Could not write the clipboard")
+}
+
+#[cfg(not(feature = "clipboard"))]
+pub fn read() -> Result<String, Error> {
+    bail!("align was built without clipboard support")
+}
+
+#[cfg(not(feature = "clipboard"))]
+pub fn write(_text: &[u8]) -> Result<(), Error> {
+    bail!("align was built without clipboard support")
+}
@@ -0,0 +1,205 @@
+use {
+    crate::{number_value, string_serde, AlignError},
+    regex::Regex,
+    serde::{Deserialize, Deserializer, Serialize, Serializer},
+    std::{
+        fmt::{self, Display, Formatter},
+        num::NonZeroUsize,
+    },
+};
+
+/// A foreground color.
+#[derive(Copy, Clone, Debug)]
+enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl Color {
+    /// Parses the name of a color, ignoring case.
+    fn parse(name: &str) -> Option<Color> {
+        let color = match &*name.to_ascii_lowercase() {
+            "black" => Color::Black,
+            "red" => Color::Red,
+            "green" => Color::Green,
+            "yellow" => Color::Yellow,
+            "blue" => Color::Blue,
+            "magenta" => Color::Magenta,
+            "cyan" => Color::Cyan,
+            "white" => Color::White,
+            _ => return None,
+        };
+        Some(color)
+    }
+
+    /// The escape sequence that selects the color.
+    fn escape(self) -> &'static str {
+        match self {
+            Color::Black => "\x1b[30m",
+            Color::Red => "\x1b[31m",
+            Color::Green => "\x1b[32m",
+            Color::Yellow => "\x1b[33m",
+            Color::Blue => "\x1b[34m",
+            Color::Magenta => "\x1b[35m",
+            Color::Cyan => "\x1b[36m",
+            Color::White => "\x1b[37m",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Test {
+    /// The comparison operator and the value.
+    Compare(&'static str, String),
+    Matches(Regex),
+}
+
+/// A rule that colors the cells of a column, or whole rows, whose cell satisfies a test.
+#[derive(Clone, Debug)]
+pub struct ColorRule {
+    /// The rule as given by the user.
+    source: String,
+    /// The column, starting at 1.
+    column: NonZeroUsize,
+    test: Test,
+    color: Color,
+    /// Whether the whole row is colored.
+    row: bool,
+}
+
+impl Display for ColorRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+string_serde!(ColorRule, parse_color_rule);
+
+/// The comparison operators. Longer operators come before their prefixes.
+const OPERATORS: [&str; 7] = [">=", "<=", "==", "!=", ">", "<", "~"];
+
+/// Parses a rule such as `3>=90:red` or `1~^ERR:yellow:row`.
+pub fn parse_color_rule(s: &str) -> Result<ColorRule, AlignError> {
+    let invalid = |msg: &str| AlignError::Value(format!("Invalid color rule `{s}`: {msg}"));
+    let digits = s.bytes().take_while(|c| c.is_ascii_digit()).count();
+    let column = s[..digits]
+        .parse()
+        .map_err(|_| invalid("expected a column"))?;
+    let rest = &s[digits..];
+    let op = OPERATORS
+        .into_iter()
+        .find(|op| rest.starts_with(op))
+        .ok_or_else(|| invalid("expected one of >=, <=, ==, !=, >, <, or ~"))?;
+    let rest = &rest[op.len()..];
+    let (rest, row) = match rest.strip_suffix(":row") {
+        Some(rest) => (rest, true),
+        None => (rest, false),
+    };
+    let (value, color) = rest
+        .rsplit_once(':')
+        .ok_or_else(|| invalid("expected `:color`"))?;
+    let color = Color::parse(color).ok_or_else(|| invalid("unknown color"))?;
+    let test = match op {
+        "~" => Test::Matches(Regex::new(value).map_err(|e| invalid(&e.to_string()))?),
+        _ => Test::Compare(op, value.to_string()),
+    };
+    Ok(ColorRule {
+        source: s.to_string(),
+        column,
+        test,
+        color,
+        row,
+    })
+}
+
+impl ColorRule {
+    fn matches(&self, cells: &[&str], decimal: char) -> bool {
+        let Some(cell) = cells.get(self.column.get() - 1) else {
+            return false;
+        };
+        let (op, expected) = match &self.test {
+            Test::Matches(regex) => return regex.is_match(cell),
+            Test::Compare(op, expected) => (*op, expected),
+        };
+        // Numbers are compared by value and everything else as text.
+        let ordering = match (number_value(cell, decimal), number_value(expected, decimal)) {
+            (Some(a), Some(b)) => a.partial_cmp(&b),
+            _ => Some((*cell).cmp(&**expected)),
+        };
+        let Some(ordering) = ordering else {
+            return false;
+        };
+        match op {
+            ">=" => ordering.is_ge(),
+            "<=" => ordering.is_le(),
+            "==" => ordering.is_eq(),
+            "!=" => ordering.is_ne(),
+            ">" => ordering.is_gt(),
+            _ => ordering.is_lt(),
+        }
+    }
+}
+
+/// Returns the escape sequence of the first row rule that matches the cells.
+pub fn row_color(rules: &[ColorRule], cells: &[&str], decimal: char) -> Option<&'static str> {
+    rules
+        .iter()
+        .find(|r| r.row && r.matches(cells, decimal))
+        .map(|r| r.color.escape())
+}
+
+/// Returns the escape sequence of the first cell rule of column `i`, starting at 0, that
+/// matches the cells.
+pub fn cell_color(
+    rules: &[ColorRule],
+    i: usize,
+    cells: &[&str],
+    decimal: char,
+) -> Option<&'static str> {
+    rules
+        .iter()
+        .find(|r| !r.row && r.column.get() == i + 1 && r.matches(cells, decimal))
+        .map(|r| r.color.escape())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let rule = parse_color_rule("3>=90:red").unwrap();
+        assert_eq!(rule.column.get(), 3);
+        assert!(matches!(rule.test, Test::Compare(">=", ref v) if v == "90"));
+        assert!(!rule.row);
+        let rule = parse_color_rule("1~^ERR:Yellow:row").unwrap();
+        assert!(matches!(rule.test, Test::Matches(_)));
+        assert!(matches!(rule.color, Color::Yellow));
+        assert!(rule.row);
+        assert_eq!(rule.to_string(), "1~^ERR:Yellow:row");
+        for invalid in ["x>1:red", "1?1:red", "1>1", "1>1:pink", "1~(:red"] {
+            assert!(parse_color_rule(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn matches() {
+        let rules = [
+            parse_color_rule("2>=90:red").unwrap(),
+            parse_color_rule("1==b:green:row").unwrap(),
+        ];
+        // Numbers are compared by value, so 100 is not less than 90.
+        assert_eq!(cell_color(&rules, 1, &["a", "100"], '.'), Some("\x1b[31m"));
+        assert_eq!(cell_color(&rules, 1, &["a", "89.5"], '.'), None);
+        assert_eq!(cell_color(&rules, 0, &["a", "100"], '.'), None);
+        assert_eq!(cell_color(&rules, 1, &["a"], '.'), None);
+        assert_eq!(row_color(&rules, &["b", "1"], '.'), Some("\x1b[32m"));
+        assert_eq!(row_color(&rules, &["c", "1"], '.'), None);
+    }
+}
@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "cli")]
+use {
+    anyhow::Error,
+    std::{
+        io::{self, BufRead, BufReader},
+        path::{Path, PathBuf},
+    },
+};
+
+/// How compressed inputs are recognized.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+pub enum Decompress {
+    /// Recognize compressed inputs, including stdin, by their content.
+    Auto,
+    /// Never decompress inputs.
+    Never,
+}
+
+#[cfg(feature = "cli")]
+#[derive(Copy, Clone)]
+pub enum Format {
+    Gzip,
+    Zstd,
+    Xz,
+}
+
+/// The file extension and magic bytes of each format.
+#[cfg(feature = "cli")]
+const FORMATS: [(Format, &str, &[u8]); 3] = [
+    (Format::Gzip, "gz", b"\x1f\x8b"),
+    (Format::Zstd, "zst", b"\x28\xb5\x2f\xfd"),
+    (Format::Xz, "xz", b"\xfd7zXZ\0"),
+];
+
+/// Returns the format of a compressed file based on its extension.
+#[cfg(feature = "cli")]
+pub fn by_extension(path: &Path) -> Option<Format> {
+    let ext = path.extension()?;
+    FORMATS
+        .iter()
+        .find(|(_, e, _)| ext == *e)
+        .map(|&(format, _, _)| format)
+}
+
+/// Returns the format of compressed data based on its first bytes.
+#[cfg(feature = "cli")]
+pub fn by_content(data: &[u8]) -> Option<Format> {
+    FORMATS
+        .iter()
+        .find(|(_, _, magic)| data.starts_with(magic))
+        .map(|&(format, _, _)| format)
+}
+
+/// Returns the format in which `mode` decompresses the input at `path`, or stdin if `path`
+/// is `None`. `head` returns the first bytes of the input.
+///
+/// By default, files are decompressed based on their extension and stdin is not
+/// decompressed.
+#[cfg(feature = "cli")]
+pub fn select<'a>(
+    mode: Option<Decompress>,
+    path: Option<&Path>,
+    head: impl FnOnce() -> io::Result<&'a [u8]>,
+) -> io::Result<Option<Format>> {
+    Ok(match mode {
+        None => path.and_then(by_extension),
+        Some(Decompress::Auto) => by_content(head()?),
+        Some(Decompress::Never) => None,
+    })
+}
+
+/// Returns the path of a compressed file without the compression extension.
+///
+/// The `.editorconfig` settings of `app.log.gz` are those of `app.log`.
+#[cfg(feature = "cli")]
+pub fn strip_extension(path: &Path) -> PathBuf {
+    match by_extension(path) {
+        Some(_) => path.with_extension(""),
+        None => path.to_path_buf(),
+    }
+}
+
+/// Decompresses the input on the fly.
+#[cfg(feature = "cli")]
+pub fn decoder<'a>(
+    input: impl BufRead + 'a,
+    format: Format,
+) -> Result<Box<dyn BufRead + 'a>, Error> {
+    let res: Box<dyn BufRead + 'a> = match format {
+        Format::Gzip => Box::new(BufReader::new(flate2::bufread::MultiGzDecoder::new(input))),
+        #[cfg(not(target_arch = "wasm32"))]
+        Format::Zstd => Box::new(BufReader::new(zstd::Decoder::with_buffer(input)?)),
+        #[cfg(not(target_arch = "wasm32"))]
+        Format::Xz => Box::new(BufReader::new(xz2::bufread::XzDecoder::new_multi_decoder(
+            input,
+        ))),
+        // The zstd and xz crates bind C libraries that do not build for wasm32.
+        #[cfg(target_arch = "wasm32")]
+        Format::Zstd | Format::Xz => anyhow::bail!("zstd and xz are not supported on wasm32"),
+    };
+    Ok(res)
+}
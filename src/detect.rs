@@ -0,0 +1,90 @@
+use crate::{
+    is_indent, is_number, log::info, split_fields, split_whitespace, AlignConfig, Diagnostic,
+};
+
+/// The number of non-blank lines inspected.
+const SAMPLE: usize = 100;
+
+/// The candidate separators in order of preference. `None` stands for runs of two or more
+/// spaces.
+const CANDIDATES: [Option<&str>; 5] = [Some("\t"), Some(","), Some("|"), Some(";"), None];
+
+/// Guesses the field separator and whether there is a header from the first lines of the
+/// input and stores them in `opts`.
+pub fn detect_separator<'a>(
+    opts: &mut AlignConfig,
+    lines: impl Iterator<Item = &'a str>,
+    log: &mut Vec<Diagnostic>,
+) {
+    let sample: Vec<&[u8]> = lines
+        .map(|l| l.as_bytes())
+        .filter(|l| !l.iter().all(|&c| is_indent(c)))
+        .take(SAMPLE)
+        .collect();
+    let mut best = None;
+    let mut best_score = sample.len() * 4 / 5;
+    for candidate in CANDIDATES {
+        opts.two_space = candidate.is_none();
+        let counts: Vec<usize> = sample
+            .iter()
+            .map(|line| {
+                let fields = match candidate {
+                    Some(sep) => split_fields(line, sep.as_bytes(), opts),
+                    None => split_whitespace(line, opts),
+                };
+                fields.iter().filter(|(start, end)| start < end).count()
+            })
+            .collect();
+        for &count in &counts {
+            if count < 2 {
+                continue;
+            }
+            let score = counts.iter().filter(|&&c| c == count).count();
+            if score > best_score {
+                best = Some(candidate);
+                best_score = score;
+            }
+        }
+    }
+    opts.two_space = best == Some(None);
+    match best {
+        Some(Some(sep)) => {
+            info!(log, 1, "detected the separator {sep:?}");
+            opts.field_sep = Some(sep.to_string());
+        }
+        Some(None) => info!(
+            log,
+            1, "detected runs of two or more spaces as the separator"
+        ),
+        None => info!(log, 1, "detected no separator"),
+    }
+    if !opts.header && has_header(opts, &sample) {
+        info!(log, 1, "detected a header");
+        opts.header = true;
+    }
+}
+
+/// Returns whether the first line is a header: none of its cells is a number, while all
+/// cells of some column below it are numbers.
+fn has_header(opts: &AlignConfig, sample: &[&[u8]]) -> bool {
+    let cells = |line: &[u8]| -> Vec<String> {
+        let fields = match &opts.field_sep {
+            Some(sep) => split_fields(line, sep.as_bytes(), opts),
+            None => split_whitespace(line, opts),
+        };
+        let cell = |&(start, end): &(usize, usize)| String::from_utf8_lossy(&line[start..end]);
+        fields.iter().map(|f| cell(f).into_owned()).collect()
+    };
+    let [first, rest @ ..] = sample else {
+        return false;
+    };
+    let header = cells(first);
+    if rest.is_empty() || header.iter().any(|c| is_number(c, opts.decimal())) {
+        return false;
+    }
+    let rows: Vec<_> = rest.iter().map(|line| cells(line)).collect();
+    (0..header.len()).any(|i| {
+        rows.iter()
+            .all(|row| row.get(i).is_some_and(|c| is_number(c, opts.decimal())))
+    })
+}
@@ -0,0 +1,52 @@
+use crate::{mapped::LineText, split, AlignConfig, Words};
+
+/// Tokenizes the lines of a unified diff.
+#[derive(Default)]
+pub struct Diff {
+    /// The number of lines of the old file remaining in the current hunk.
+    old: usize,
+    /// The number of lines of the new file remaining in the current hunk.
+    new: usize,
+}
+
+impl Diff {
+    pub fn parse(&mut self, line: LineText, opts: &AlignConfig) -> Words {
+        let bytes = line.as_bytes();
+        if self.old == 0 && self.new == 0 {
+            if let Some((old, new)) = parse_hunk_header(&line) {
+                self.old = old;
+                self.new = new;
+            }
+            return Words::verbatim(line);
+        }
+        match bytes.first() {
+            None => return Words::with_words(line, Vec::new()),
+            Some(b' ') => {
+                self.old = self.old.saturating_sub(1);
+                self.new = self.new.saturating_sub(1);
+            }
+            Some(b'-') => self.old = self.old.saturating_sub(1),
+            Some(b'+') => self.new = self.new.saturating_sub(1),
+            _ => return Words::verbatim(line),
+        }
+        let words = split(&line[1..], opts)
+            .into_iter()
+            .map(|(start, end)| (start + 1, end + 1))
+            .collect();
+        let mut words = Words::with_words(line, words);
+        words.prefix = 1;
+        words
+    }
+}
+
+/// Returns the number of old and new lines of a `@@ -l,s +l,s @@` header.
+fn parse_hunk_header(line: &str) -> Option<(usize, usize)> {
+    let mut parts = line.strip_prefix("@@ ")?.split(' ');
+    let count = |range: &str| match range.split_once(',') {
+        Some((_, count)) => count.parse().ok(),
+        None => Some(1),
+    };
+    let old = count(parts.next()?.strip_prefix('-')?)?;
+    let new = count(parts.next()?.strip_prefix('+')?)?;
+    Some((old, new))
+}
@@ -0,0 +1,95 @@
+use ec4rs::property::IndentStyle;
+#[cfg(feature = "cli")]
+use {
+    anyhow::{Context, Error},
+    ec4rs::property::{EndOfLine, TabWidth, TrimTrailingWs},
+    std::path::Path,
+};
+
+/// The output conventions of a file as configured in `.editorconfig`.
+#[derive(Clone)]
+pub struct EditorConfig {
+    indent_style: Option<IndentStyle>,
+    tab_width: usize,
+    eol: &'static [u8],
+    trim_trailing_whitespace: bool,
+}
+
+impl Default for EditorConfig {
+    fn default() -> Self {
+        Self {
+            indent_style: None,
+            tab_width: 8,
+            eol: b"\n",
+            trim_trailing_whitespace: false,
+        }
+    }
+}
+
+impl EditorConfig {
+    #[cfg(feature = "cli")]
+    pub fn of(path: &Path) -> Result<EditorConfig, Error> {
+        let mut props = ec4rs::properties_of(path)
+            .with_context(|| format!("Could not load .editorconfig for {}", path.display()))?;
+        props.use_fallbacks();
+        let mut config = EditorConfig {
+            indent_style: props.get::<IndentStyle>().ok(),
+            ..Default::default()
+        };
+        if let Ok(TabWidth::Value(n)) = props.get::<TabWidth>() {
+            if n > 0 {
+                config.tab_width = n;
+            }
+        }
+        if let Ok(eol) = props.get::<EndOfLine>() {
+            config.eol = match eol {
+                EndOfLine::Lf => b"\n",
+                EndOfLine::CrLf => b"\r\n",
+                EndOfLine::Cr => b"\r",
+            };
+        }
+        if let Ok(TrimTrailingWs::Value(trim)) = props.get::<TrimTrailingWs>() {
+            config.trim_trailing_whitespace = trim;
+        }
+        Ok(config)
+    }
+
+    /// Overrides the configured line ending.
+    pub fn set_eol(&mut self, eol: &'static [u8]) {
+        self.eol = eol;
+    }
+
+    /// Converts the indentation to the configured indent style.
+    pub fn reindent(&self, indent: &[u8]) -> Vec<u8> {
+        let Some(style) = self.indent_style else {
+            return indent.to_vec();
+        };
+        let mut width = 0;
+        for &c in indent {
+            match c {
+                b'\t' => width += self.tab_width - width % self.tab_width,
+                _ => width += 1,
+            }
+        }
+        let mut res = Vec::new();
+        if let IndentStyle::Tabs = style {
+            res.resize(width / self.tab_width, b'\t');
+            width %= self.tab_width;
+        }
+        res.resize(res.len() + width, b' ');
+        res
+    }
+
+    /// Finishes an output line.
+    pub fn end_line(&self, line: &mut Vec<u8>) {
+        if self.trim_trailing_whitespace {
+            let len = line
+                .iter()
+                .rposition(|&c| c != b' ' && c != b'\t')
+                .map(|i| i + 1)
+                .unwrap_or(0);
+            line.truncate(len);
+        }
+        line.extend_from_slice(self.eol);
+    }
+}
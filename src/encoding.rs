@@ -0,0 +1,78 @@
+use {
+    crate::{string_serde, AlignError},
+    encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252},
+    serde::{Deserialize, Deserializer, Serialize, Serializer},
+    std::{
+        borrow::Cow,
+        fmt::{self, Display, Formatter},
+    },
+};
+
+/// The encoding of the input.
+#[derive(Copy, Clone, Debug)]
+pub enum InputEncoding {
+    /// Detect the encoding from the byte order mark. Input without one is UTF-8 if it is
+    /// valid UTF-8 and Windows-1252 otherwise.
+    Auto,
+    Fixed(&'static Encoding),
+}
+
+impl Display for InputEncoding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            InputEncoding::Auto => f.write_str("auto"),
+            InputEncoding::Fixed(encoding) => f.write_str(encoding.name()),
+        }
+    }
+}
+
+string_serde!(InputEncoding, parse_encoding);
+
+/// Parses an encoding label such as `utf-16le`, `latin1`, `shift_jis`, or `auto`.
+pub fn parse_encoding(s: &str) -> Result<InputEncoding, AlignError> {
+    if s.eq_ignore_ascii_case("auto") {
+        return Ok(InputEncoding::Auto);
+    }
+    Encoding::for_label(s.as_bytes())
+        .map(InputEncoding::Fixed)
+        .ok_or_else(|| AlignError::Value(format!("Unknown encoding `{s}`")))
+}
+
+/// Decodes the input and returns the text together with the encoding that was used.
+///
+/// A byte order mark takes precedence over the given encoding.
+pub fn decode(bytes: &[u8], encoding: InputEncoding) -> (String, &'static Encoding) {
+    let encoding = match encoding {
+        InputEncoding::Fixed(encoding) => encoding,
+        InputEncoding::Auto => match Encoding::for_bom(bytes) {
+            Some((encoding, _)) => encoding,
+            None if std::str::from_utf8(bytes).is_ok() => UTF_8,
+            None => WINDOWS_1252,
+        },
+    };
+    let (text, encoding, _) = encoding.decode(bytes);
+    (text.into_owned(), encoding)
+}
+
+/// Encodes UTF-8 output in the given encoding.
+///
+/// Characters that cannot be represented are written as numeric character references.
+pub fn encode<'a>(bytes: &'a [u8], encoding: &'static Encoding) -> Cow<'a, [u8]> {
+    if encoding == UTF_8 {
+        return Cow::Borrowed(bytes);
+    }
+    let text = String::from_utf8_lossy(bytes);
+    // encoding_rs only decodes UTF-16.
+    if encoding == UTF_16LE || encoding == UTF_16BE {
+        let mut res = Vec::with_capacity(2 * text.len());
+        for unit in text.encode_utf16() {
+            match encoding == UTF_16LE {
+                true => res.extend_from_slice(&unit.to_le_bytes()),
+                false => res.extend_from_slice(&unit.to_be_bytes()),
+            }
+        }
+        return Cow::Owned(res);
+    }
+    let (res, _, _) = encoding.encode(&text);
+    Cow::Owned(res.into_owned())
+}
@@ -0,0 +1,167 @@
+use crate::{is_indent, trim};
+
+/// Splits a `KEY=value # comment` assignment into the key, the `=`, the value, and the
+/// comment, if any.
+///
+/// Returns `None` if the line is not an assignment.
+pub fn split_assignment(line: &[u8]) -> Option<Vec<(usize, usize)>> {
+    let eq = line.iter().position(|&c| c == b'=')?;
+    let (start, end) = trim(line, 0, eq);
+    let key = &line[start..end];
+    let name = match key.strip_prefix(b"export") {
+        Some(name) if name.first().copied().is_some_and(is_indent) => {
+            let (s, e) = trim(name, 0, name.len());
+            &name[s..e]
+        }
+        _ => key,
+    };
+    if name.is_empty() || name[0] == b'#' || name.iter().copied().any(is_indent) {
+        return None;
+    }
+    let mut words = vec![(start, end), (eq, eq + 1)];
+    let value = eq + 1;
+    let mut comment = None;
+    let mut quote = None;
+    let mut esc = false;
+    for (pos, &c) in line.iter().enumerate().skip(value) {
+        match quote {
+            Some(q) => {
+                if c == q && !esc {
+                    quote = None;
+                }
+                esc = q == b'"' && !esc && c == b'\\';
+            }
+            None if c == b'"' || c == b'\'' => quote = Some(c),
+            None if c == b'#' && (pos == value || is_indent(line[pos - 1])) => {
+                comment = Some(pos);
+                break;
+            }
+            None => {}
+        }
+    }
+    let (start, end) = trim(line, value, comment.unwrap_or(line.len()));
+    if start < end || comment.is_some() {
+        words.push((start, end));
+    }
+    if let Some(comment) = comment {
+        words.push(trim(line, comment, line.len()));
+    }
+    Some(words)
+}
+
+/// Tracks the here-documents of a shell script, whose bodies are printed as is.
+#[derive(Default)]
+pub struct Heredoc {
+    /// The delimiters of the pending here-documents and whether leading tabs are stripped
+    /// from their lines, in order.
+    pending: Vec<(String, bool)>,
+    /// Whether the current line is part of the body of the first pending here-document.
+    body: bool,
+}
+
+impl Heredoc {
+    /// Returns whether the line belongs to the body of a here-document, including the
+    /// line with the delimiter.
+    pub fn skip(&mut self, line: &str) -> bool {
+        if self.body {
+            let (delim, tabs) = &self.pending[0];
+            let end = match tabs {
+                true => line.trim_start_matches('\t'),
+                false => line,
+            };
+            if end == delim {
+                self.pending.remove(0);
+                self.body = !self.pending.is_empty();
+            }
+            return true;
+        }
+        self.pending.extend(find_heredocs(line.as_bytes()));
+        self.body = !self.pending.is_empty();
+        false
+    }
+}
+
+/// Returns the delimiters of the here-documents started by `<<` in the line.
+fn find_heredocs(line: &[u8]) -> Vec<(String, bool)> {
+    let mut res = Vec::new();
+    let mut quote = None;
+    let mut esc = false;
+    let mut pos = 0;
+    while pos < line.len() {
+        let c = line[pos];
+        pos += 1;
+        match quote {
+            Some(q) => {
+                if c == q && !esc {
+                    quote = None;
+                }
+                esc = q == b'"' && !esc && c == b'\\';
+                continue;
+            }
+            None if esc => esc = false,
+            None if c == b'\\' => esc = true,
+            None if c == b'"' || c == b'\'' => quote = Some(c),
+            None if c == b'#' && (pos == 1 || is_indent(line[pos - 2])) => break,
+            // A here-string.
+            None if line[pos - 1..].starts_with(b"<<<") => pos += 2,
+            None if line[pos - 1..].starts_with(b"<<") => {
+                pos += 1;
+                let tabs = line.get(pos) == Some(&b'-');
+                pos += usize::from(tabs);
+                while line.get(pos).copied().is_some_and(is_indent) {
+                    pos += 1;
+                }
+                let mut delim = Vec::new();
+                let mut word_quote = None;
+                while let Some(&c) = line.get(pos) {
+                    match word_quote {
+                        Some(q) if c == q => word_quote = None,
+                        Some(_) => delim.push(c),
+                        None if c == b'"' || c == b'\'' => word_quote = Some(c),
+                        None if c == b'\\' => {}
+                        None if is_indent(c) || b";|&<>()".contains(&c) => break,
+                        None => delim.push(c),
+                    }
+                    pos += 1;
+                }
+                if !delim.is_empty() {
+                    res.push((String::from_utf8_lossy(&delim).into_owned(), tabs));
+                }
+            }
+            None => {}
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heredocs() {
+        let find = |line: &str| find_heredocs(line.as_bytes());
+        assert_eq!(find("cat <<EOF"), [("EOF".to_string(), false)]);
+        assert_eq!(find("cat <<-'END' >x"), [("END".to_string(), true)]);
+        assert_eq!(find(r#"a << "A" && b <<B"#).len(), 2);
+        assert!(find("cat <<< word").is_empty());
+        assert!(find("echo '<<EOF'").is_empty());
+        assert!(find("a=1 # <<EOF").is_empty());
+    }
+
+    #[test]
+    fn skip() {
+        let mut heredoc = Heredoc::default();
+        let lines = [
+            "A=1",
+            "cat <<-EOF; cat <<X",
+            "B=2",
+            "\tEOF",
+            "C=3",
+            "X",
+            "D=4",
+        ];
+        let skipped: Vec<bool> = lines.iter().map(|l| heredoc.skip(l)).collect();
+        assert_eq!(skipped, [false, false, true, true, true, true, false]);
+    }
+}
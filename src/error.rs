@@ -0,0 +1,111 @@
+use {
+    crate::DIRECTIVES,
+    std::{
+        error::Error,
+        fmt::{self, Display, Formatter},
+        io,
+        path::PathBuf,
+    },
+    unicode_width::UnicodeWidthStr,
+};
+
+/// An error of the library.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum AlignError {
+    /// A positioning spec is invalid.
+    Spec {
+        spec: String,
+        /// The byte offset of the offending character.
+        offset: usize,
+        message: String,
+    },
+    /// An option has an invalid value.
+    Value(String),
+    /// The column stops are not strictly increasing.
+    Stops,
+    /// A line is longer than the maximum line length.
+    LineTooLong {
+        /// The number of the line, starting at 1.
+        line: usize,
+        max: usize,
+    },
+    /// A row has a different number of cells than the first row.
+    RaggedRow {
+        /// The number of the line, starting at 1.
+        line: usize,
+        /// The number of cells of the first row.
+        expected: usize,
+        cells: Vec<String>,
+    },
+    /// A command that rewrites cells failed.
+    Command(String),
+    /// The columns are too wide to be printed.
+    WidthOverflow,
+    /// Reading the input or writing the output failed.
+    Io {
+        /// The file that could not be read, if known.
+        path: Option<PathBuf>,
+        source: io::Error,
+    },
+}
+
+impl Display for AlignError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            AlignError::Spec {
+                spec,
+                offset,
+                message,
+            } => {
+                let caret = spec[..*offset].width();
+                write!(
+                    f,
+                    "{message} at offset {offset}\n\n    {spec}\n    {:caret$}^\n\n{DIRECTIVES}",
+                    ""
+                )
+            }
+            AlignError::Value(message) | AlignError::Command(message) => f.write_str(message),
+            AlignError::Stops => f.write_str("Column stops must be strictly increasing"),
+            AlignError::LineTooLong { line, max } => {
+                write!(f, "Line {line} is longer than {max} bytes")
+            }
+            AlignError::RaggedRow {
+                line,
+                expected,
+                cells,
+            } => {
+                let len = cells.len();
+                write!(
+                    f,
+                    "Line {line} has {len} cells instead of {expected}: {cells:?}"
+                )
+            }
+            AlignError::WidthOverflow => f.write_str("The columns are too wide"),
+            AlignError::Io {
+                path: Some(path), ..
+            } => write!(f, "Could not read {}", path.display()),
+            AlignError::Io { path: None, .. } => {
+                f.write_str("Could not read the input or write the output")
+            }
+        }
+    }
+}
+
+impl Error for AlignError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AlignError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for AlignError {
+    fn from(e: io::Error) -> Self {
+        AlignError::Io {
+            path: None,
+            source: e,
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use {
+    crate::{parse_positioning, AlignConfig},
+    anyhow::Error,
+    std::{
+        ffi::{c_char, c_int, CStr, CString},
+        ptr,
+    },
+};
+
+/// An alignment engine.
+pub struct Align {
+    config: AlignConfig,
+    /// The result of the last call of `align_process`.
+    output: CString,
+    /// The message of the last error.
+    error: CString,
+}
+
+impl Align {
+    fn fail(&mut self, e: Error) {
+        let msg = format!("{e:#}").replace('\0', "");
+        self.error = CString::new(msg).unwrap_or_default();
+    }
+}
+
+/// Creates an engine with the default options.
+///
+/// The engine must be freed with `align_free`.
+#[no_mangle]
+pub extern "C" fn align_new() -> *mut Align {
+    Box::into_raw(Box::new(Align {
+        config: AlignConfig::default(),
+        output: CString::default(),
+        error: CString::default(),
+    }))
+}
+
+/// Sets the positioning spec, e.g. `<><`.
+///
+/// Returns 0 on success and -1 if the spec is invalid.
+///
+/// # Safety
+///
+/// `align` must have been created by `align_new` and `spec` must be a NUL-terminated
+/// string.
+#[no_mangle]
+pub unsafe extern "C" fn align_set_spec(align: *mut Align, spec: *const c_char) -> c_int {
+    let align = &mut *align;
+    let res = CStr::from_ptr(spec)
+        .to_str()
+        .map_err(Error::from)
+        .and_then(|spec| Ok(parse_positioning(spec)?));
+    match res {
+        Ok(positioning) => {
+            align.config.positioning = positioning;
+            0
+        }
+        Err(e) => {
+            align.fail(e);
+            -1
+        }
+    }
+}
+
+/// Aligns the columns of `text`.
+///
+/// Returns the aligned text, or NULL on error. The text is owned by the engine and stays
+/// valid until the next call of `align_process` or `align_free`.
+///
+/// # Safety
+///
+/// `align` must have been created by `align_new` and `text` must be a NUL-terminated
+/// string.
+#[no_mangle]
+pub unsafe extern "C" fn align_process(align: *mut Align, text: *const c_char) -> *const c_char {
+    let align = &mut *align;
+    let res = CStr::from_ptr(text)
+        .to_str()
+        .map_err(Error::from)
+        .and_then(|text| Ok(crate::align(&align.config, text)?))
+        .and_then(|output| Ok(CString::new(output)?));
+    match res {
+        Ok(output) => {
+            align.output = output;
+            align.output.as_ptr()
+        }
+        Err(e) => {
+            align.fail(e);
+            ptr::null()
+        }
+    }
+}
+
+/// Returns the message of the last error.
+///
+/// The message is owned by the engine and stays valid until the next error or
+/// `align_free`.
+///
+/// # Safety
+///
+/// `align` must have been created by `align_new`.
+#[no_mangle]
+pub unsafe extern "C" fn align_last_error(align: *const Align) -> *const c_char {
+    (*align).error.as_ptr()
+}
+
+/// Frees an engine. Does nothing if `align` is NULL.
+///
+/// # Safety
+///
+/// `align` must be NULL or have been created by `align_new`.
+#[no_mangle]
+pub unsafe extern "C" fn align_free(align: *mut Align) {
+    if !align.is_null() {
+        drop(Box::from_raw(align));
+    }
+}
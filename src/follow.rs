@@ -0,0 +1,111 @@
+use {
+    crate::{
+        cli::Cli, compute_groups, editorconfig::EditorConfig, parse_lines, transform, write_inputs,
+        DynVec, Input,
+    },
+    anyhow::{bail, Context, Error},
+    serde::{Deserialize, Serialize},
+    std::{
+        fs::File,
+        io::{stdout, BufRead, BufReader, Seek, SeekFrom, Write},
+        thread,
+        time::Duration,
+    },
+};
+
+/// How columns change once lines have been printed.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+pub enum Widen {
+    /// Columns grow to fit wider cells.
+    Grow,
+    /// Columns keep the widths of the first lines. Wider cells overflow.
+    Never,
+}
+
+/// The time to wait for the file to grow.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Aligns the lines of a growing file in blocks.
+pub fn follow(cli: &mut Cli, widen: Widen) -> Result<(), Error> {
+    let verbosity = cli.verbosity();
+    let opts = &mut cli.config;
+    let [path] = &cli.files[..] else {
+        bail!("--follow requires exactly one file");
+    };
+    let path = path.clone();
+    let mut file = BufReader::new(
+        File::open(&path).with_context(|| format!("Could not open {}", path.display()))?,
+    );
+    let mut config = EditorConfig::of(&path)?;
+    if opts.null {
+        config.set_eol(b"\0");
+    }
+    let delim = opts.record_delim();
+    let mut indent = None;
+    let mut widths: Option<DynVec<usize>> = None;
+    let mut partial = Vec::new();
+    // Whether the next line is the first line of the file.
+    let mut first = true;
+    let mut bom = false;
+    let mut stdout = stdout().lock();
+    loop {
+        let mut text = Vec::new();
+        while file.read_until(delim, &mut partial)? > 0 {
+            if let Some(line) = partial.strip_suffix(&[delim]) {
+                let line = match delim {
+                    b'\n' => line.strip_suffix(b"\r").unwrap_or(line),
+                    _ => line,
+                };
+                let mut line = String::from_utf8_lossy(line).into_owned();
+                if let Some(rest) = line.strip_prefix('\u{feff}').filter(|_| first) {
+                    line = rest.to_string();
+                    bom = true;
+                }
+                first = false;
+                text.push(line);
+                partial.clear();
+            }
+        }
+        if text.is_empty() {
+            // Start over if the file has been truncated.
+            if file.get_ref().metadata()?.len() < file.stream_position()? {
+                file.seek(SeekFrom::Start(0))?;
+                partial.clear();
+                first = true;
+            }
+            thread::sleep(POLL_INTERVAL);
+            continue;
+        }
+        let mut log = Vec::new();
+        let parsed = parse_lines(opts, text, &mut log);
+        verbosity.report(&mut log);
+        let (block_indent, lines) = parsed?;
+        let mut inputs = [Input {
+            indent: indent.clone().or(block_indent),
+            lines,
+            config: config.clone(),
+            encoding: encoding_rs::UTF_8,
+            bom: std::mem::take(&mut bom),
+        }];
+        indent = inputs[0].indent.clone();
+        transform(opts, &mut inputs)?;
+        let mut groups = compute_groups(opts, &mut inputs);
+        match (widen, &widths) {
+            (Widen::Never, Some(widths)) => {
+                for positioning in &mut groups {
+                    positioning.max_width = widths.clone();
+                }
+            }
+            _ => {
+                // Later blocks are at least as wide as this one.
+                let last = groups[groups.len() - 1].max_width.clone();
+                opts.positioning.max_width = last.clone();
+                widths = Some(last);
+            }
+        }
+        write_inputs(opts, &inputs, &groups, &mut stdout)?;
+        stdout.flush()?;
+    }
+}
@@ -0,0 +1,96 @@
+use {
+    crate::{Alignment::Right, Input, Positioning},
+    serde::{Deserialize, Serialize},
+};
+
+/// The case of hexadecimal digits.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+pub enum HexCase {
+    /// Lowercase digits.
+    Lower,
+    /// Uppercase digits.
+    Upper,
+}
+
+#[derive(Default)]
+struct Column {
+    /// Whether a cell is not a hexadecimal number.
+    invalid: bool,
+    /// Whether a cell is recognizably hexadecimal.
+    hex: bool,
+    prefix: bool,
+    digits: usize,
+    /// The number of hexadecimal cells and of those among them without letters.
+    cells: usize,
+    decimal: usize,
+}
+
+impl Column {
+    /// Whether the column is rewritten. Columns without a `0x` prefix must consist mostly
+    /// of decimal numbers, so that columns of words such as `cafe` are left alone.
+    fn is_hex(&self) -> bool {
+        !self.invalid && self.hex && (self.prefix || 2 * self.decimal > self.cells)
+    }
+}
+
+/// Splits a hexadecimal number into whether it has a `0x` prefix and its digits.
+fn parse(s: &str) -> Option<(bool, &str)> {
+    let (prefix, digits) = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(digits) => (true, digits),
+        None => (false, s),
+    };
+    let valid = digits.len() > 0 && digits.bytes().all(|c| c.is_ascii_hexdigit());
+    valid.then_some((prefix, digits))
+}
+
+/// Normalizes and right aligns the hexadecimal columns.
+pub fn normalize(positioning: &mut Positioning, inputs: &mut [Input], case: HexCase) {
+    let mut columns: Vec<Column> = Vec::new();
+    for line in inputs.iter().flat_map(|i| &i.lines).filter(|l| !l.header) {
+        for (i, word) in line.words.iter().enumerate() {
+            if columns.len() <= i {
+                columns.resize_with(i + 1, Default::default);
+            }
+            let column = &mut columns[i];
+            match parse(word) {
+                Some((prefix, digits)) => {
+                    let letters = digits.bytes().any(|c| c.is_ascii_alphabetic());
+                    column.hex |= prefix || letters;
+                    column.cells += 1;
+                    column.decimal += usize::from(!prefix && !letters);
+                    column.prefix |= prefix;
+                    column.digits = column.digits.max(digits.len());
+                }
+                None if word.is_empty() => {}
+                None => column.invalid = true,
+            }
+        }
+    }
+    // Explicit alignments take precedence.
+    let explicit = positioning.align.vec.len();
+    for (i, column) in columns.iter().enumerate().skip(explicit) {
+        if column.is_hex() {
+            positioning.align.set(i, Right);
+        }
+    }
+    for line in inputs.iter_mut().flat_map(|i| &mut i.lines) {
+        if line.header {
+            continue;
+        }
+        line.words.map(|i, word| {
+            let column = &columns[i];
+            if !column.is_hex() {
+                return None;
+            }
+            let (_, digits) = parse(word)?;
+            let digits = match case {
+                HexCase::Lower => digits.to_ascii_lowercase(),
+                HexCase::Upper => digits.to_ascii_uppercase(),
+            };
+            let prefix = if column.prefix { "0x" } else { "" };
+            Some(format!("{prefix}{digits:0>0$}", column.digits))
+        });
+    }
+}
@@ -0,0 +1,84 @@
+use {
+    crate::{string_serde, AlignError, Input},
+    serde::{Deserialize, Deserializer, Serialize, Serializer},
+    std::{
+        fmt::{self, Display, Formatter},
+        io::Write,
+        num::NonZeroUsize,
+        process::{Command, Stdio},
+        thread,
+    },
+};
+
+/// A shell command that rewrites the cells of a column.
+#[derive(Clone, Debug)]
+pub struct CellCommand {
+    /// The column, starting at 1.
+    pub column: NonZeroUsize,
+    pub command: String,
+}
+
+impl Display for CellCommand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.column, self.command)
+    }
+}
+
+string_serde!(CellCommand, parse_cell_command);
+
+/// Parses a cell command such as `2:tr a-z A-Z`.
+pub fn parse_cell_command(s: &str) -> Result<CellCommand, AlignError> {
+    let invalid = || AlignError::Value(format!("Expected `column:command`, found `{s}`"));
+    let (column, command) = s.split_once(':').ok_or_else(invalid)?;
+    Ok(CellCommand {
+        column: column.parse().map_err(|_| invalid())?,
+        command: command.to_string(),
+    })
+}
+
+/// Replaces the cells of the column by the output of the command.
+///
+/// The cells are written to the stdin of the command, one per line. The command must
+/// print the same number of lines.
+pub fn run(command: &CellCommand, inputs: &mut [Input]) -> Result<(), AlignError> {
+    let column = command.column.get() - 1;
+    let mut cells = String::new();
+    for line in inputs.iter().flat_map(|i| &i.lines) {
+        if let Some(cell) = line.words.iter().nth(column) {
+            cells.push_str(cell);
+            cells.push('\n');
+        }
+    }
+    let failed = |msg: &dyn Display| AlignError::Command(format!("`{}` {msg}", command.command));
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(&command.command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    let mut stdin = child.stdin.take().unwrap();
+    // Write from another thread so that the command cannot block on a full stdout.
+    let writer = thread::spawn(move || stdin.write_all(cells.as_bytes()));
+    let output = child.wait_with_output()?;
+    let _ = writer.join();
+    if !output.status.success() {
+        return Err(failed(&format_args!("failed with {}", output.status)));
+    }
+    let output = String::from_utf8(output.stdout).map_err(|_| failed(&"printed invalid UTF-8"))?;
+    let mut output = output.lines();
+    let mut missing = false;
+    for line in inputs.iter_mut().flat_map(|i| &mut i.lines) {
+        line.words.map(|i, _| {
+            if i != column {
+                return None;
+            }
+            let cell = output.next();
+            missing |= cell.is_none();
+            cell.map(|cell| cell.to_string())
+        });
+    }
+    if missing || output.next().is_some() {
+        return Err(failed(&"did not print one line per cell"));
+    }
+    Ok(())
+}
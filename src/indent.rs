@@ -0,0 +1,103 @@
+use crate::{is_indent, log::warning, Diagnostic};
+
+/// The characters of an indentation.
+#[derive(Copy, Clone, PartialEq)]
+enum Style {
+    Spaces,
+    Tabs,
+    Mixed,
+}
+
+impl Style {
+    fn of(indent: &[u8]) -> Option<Style> {
+        let tabs = indent.contains(&b'\t');
+        let spaces = indent.contains(&b' ');
+        match (tabs, spaces) {
+            (false, false) => None,
+            (true, false) => Some(Style::Tabs),
+            (false, true) => Some(Style::Spaces),
+            (true, true) => Some(Style::Mixed),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Style::Spaces => "spaces",
+            Style::Tabs => "tabs",
+            Style::Mixed => "tabs and spaces",
+        }
+    }
+}
+
+/// The number of line numbers listed in a warning.
+const MAX_LINES: usize = 10;
+
+/// Collects the lines whose indentation is inconsistent.
+#[derive(Default)]
+pub struct IndentCheck {
+    /// The number and the indentation style of the first indented line.
+    first: Option<(usize, Style)>,
+    /// The lines whose indentation mixes tabs and spaces.
+    mixed: Vec<usize>,
+    /// The lines indented with other characters than the first indented line.
+    different: Vec<usize>,
+}
+
+impl IndentCheck {
+    /// Checks the indentation of line `n`, starting at 1.
+    pub fn check(&mut self, n: usize, line: &[u8]) {
+        let len = line.iter().take_while(|&&c| is_indent(c)).count();
+        let Some(style) = Style::of(&line[..len]) else {
+            return;
+        };
+        if style == Style::Mixed {
+            self.mixed.push(n);
+        }
+        match self.first {
+            None => self.first = Some((n, style)),
+            Some((_, first)) if style != first && style != Style::Mixed => self.different.push(n),
+            _ => {}
+        }
+    }
+
+    /// Adds the warnings about the inconsistent lines to `log`.
+    pub fn warn(&self, log: &mut Vec<Diagnostic>) {
+        if !self.mixed.is_empty() {
+            warning!(
+                log,
+                "the indentation of {} mixes tabs and spaces",
+                list(&self.mixed)
+            );
+        }
+        if let (Some((first, style)), false) = (self.first, self.different.is_empty()) {
+            let verb = match self.different.len() {
+                1 => "is",
+                _ => "are",
+            };
+            warning!(
+                log,
+                "{} {verb} not indented with {} like line {first}",
+                list(&self.different),
+                style.name()
+            );
+        }
+    }
+}
+
+/// Formats line numbers as `line 1` or `lines 1, 2, 3`.
+fn list(lines: &[usize]) -> String {
+    let mut res = match lines.len() {
+        1 => "line ".to_string(),
+        _ => "lines ".to_string(),
+    };
+    let numbers: Vec<_> = lines
+        .iter()
+        .take(MAX_LINES)
+        .map(|n| n.to_string())
+        .collect();
+    res.push_str(&numbers.join(", "));
+    if lines.len() > MAX_LINES {
+        res.push_str(", ...");
+    }
+    res
+}
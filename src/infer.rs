@@ -0,0 +1,120 @@
+use {
+    crate::{number_value, timestamp, AlignConfig, Alignment, Input, Positioning},
+    std::fmt::{self, Display, Formatter},
+};
+
+/// The number of rows from which the types of the columns are inferred.
+const SAMPLE: usize = 1000;
+
+/// The inferred type of a column.
+#[derive(Copy, Clone, PartialEq)]
+pub enum Type {
+    Integer,
+    Float,
+    Date,
+    Boolean,
+    Text,
+}
+
+impl Type {
+    fn of(cell: &str, decimal: char) -> Type {
+        if number_value(cell, decimal).is_some() {
+            return match cell.contains(decimal) {
+                true => Type::Float,
+                false => Type::Integer,
+            };
+        }
+        if timestamp::is_date_time(cell) {
+            return Type::Date;
+        }
+        let boolean = ["true", "false", "yes", "no"];
+        if boolean.iter().any(|b| cell.eq_ignore_ascii_case(b)) {
+            return Type::Boolean;
+        }
+        Type::Text
+    }
+
+    /// Returns the type of a column containing values of both types.
+    fn join(self, other: Type) -> Type {
+        match (self, other) {
+            _ if self == other => self,
+            (Type::Integer, Type::Float) | (Type::Float, Type::Integer) => Type::Float,
+            _ => Type::Text,
+        }
+    }
+
+    fn alignment(self) -> Alignment {
+        match self {
+            Type::Integer => Alignment::Right,
+            Type::Float => Alignment::Decimal,
+            Type::Boolean => Alignment::Centered,
+            Type::Date | Type::Text => Alignment::Left,
+        }
+    }
+}
+
+impl Display for Type {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Type::Integer => "integer",
+            Type::Float => "float",
+            Type::Date => "date",
+            Type::Boolean => "boolean",
+            Type::Text => "text",
+        })
+    }
+}
+
+/// Infers the types of the columns from the first rows of the inputs.
+///
+/// Empty cells, headers, and lines printed as is are ignored. Columns without any other
+/// cells are text.
+pub fn infer(opts: &AlignConfig, inputs: &[Input]) -> Vec<Type> {
+    let mut types: Vec<Option<Type>> = Vec::new();
+    let rows = inputs
+        .iter()
+        .flat_map(|i| &i.lines)
+        .filter(|l| !l.header && l.rule.is_none() && !l.words.verbatim)
+        .take(SAMPLE);
+    for line in rows {
+        for (i, cell) in line.words.iter().enumerate() {
+            if cell.is_empty() {
+                continue;
+            }
+            if types.len() <= i {
+                types.resize(i + 1, None);
+            }
+            let ty = Type::of(cell, opts.decimal());
+            types[i] = Some(types[i].map_or(ty, |t| t.join(ty)));
+        }
+    }
+    types.into_iter().map(|t| t.unwrap_or(Type::Text)).collect()
+}
+
+/// Aligns the columns whose alignment is not given by the positioning according to
+/// their inferred types.
+pub fn apply(opts: &AlignConfig, inputs: &[Input], groups: &mut [Positioning]) {
+    let explicit = opts.positioning.align.vec.len();
+    let types = infer(opts, inputs);
+    for positioning in groups {
+        for (i, ty) in types.iter().enumerate().skip(explicit) {
+            positioning.align.set(i, ty.alignment());
+        }
+    }
+}
+
+/// Returns the inferred schema, one line per column with the number of the column, its
+/// type, and the cell of the header if there is one.
+#[cfg(feature = "cli")]
+pub fn schema(opts: &AlignConfig, inputs: &[Input]) -> String {
+    let header = inputs.iter().flat_map(|i| &i.lines).find(|l| l.header);
+    let mut res = String::new();
+    for (i, ty) in infer(opts, inputs).into_iter().enumerate() {
+        res.push_str(&format!("{}\t{ty}", i + 1));
+        if let Some(name) = header.and_then(|h| h.words.iter().nth(i)) {
+            res.push_str(&format!("\t{name}"));
+        }
+        res.push('\n');
+    }
+    res
+}
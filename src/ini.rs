@@ -0,0 +1,173 @@
+use crate::{mapped::LineText, trim, Words};
+
+/// Tokenizes the lines of a TOML or INI file.
+#[derive(Default)]
+pub struct Ini {
+    /// The number of section headers seen so far.
+    section: usize,
+    /// The number of unclosed brackets of a value spanning multiple lines.
+    depth: usize,
+    /// The delimiter of an unclosed string.
+    string: Option<&'static [u8]>,
+}
+
+impl Ini {
+    /// Tokenizes the next line and returns it together with the id of its section.
+    ///
+    /// A `key = value # comment` line is split into the key, the `=`, the value, and the
+    /// comment. All other lines, including the continuation lines of values spanning
+    /// multiple lines, are printed as is.
+    pub fn parse(&mut self, line: LineText) -> (Words, usize) {
+        let bytes = line.as_bytes();
+        if self.depth > 0 || self.string.is_some() {
+            self.scan(bytes, 0);
+            return (Words::verbatim(line), self.section);
+        }
+        let (start, end) = trim(bytes, 0, bytes.len());
+        if start == end {
+            return (Words::with_words(line, Vec::new()), self.section);
+        }
+        if bytes[start] == b'[' {
+            self.section += 1;
+        }
+        let eq = match bytes[start] {
+            b'[' | b'#' | b';' => None,
+            _ => find_eq(&bytes[start..]).map(|i| start + i),
+        };
+        let Some(eq) = eq else {
+            return (Words::verbatim(line), self.section);
+        };
+        let mut words = vec![trim(bytes, start, eq), (eq, eq + 1)];
+        let comment = self.scan(bytes, eq + 1);
+        let (start, end) = trim(bytes, eq + 1, comment.unwrap_or(bytes.len()));
+        if start < end || comment.is_some() {
+            words.push((start, end));
+        }
+        if let Some(comment) = comment {
+            words.push(trim(bytes, comment, bytes.len()));
+        }
+        let mut words = Words::with_words(line, words);
+        words.own_indent = true;
+        (words, self.section)
+    }
+
+    /// Tracks the strings and brackets of `line[pos..]` and returns the position of a
+    /// trailing comment.
+    fn scan(&mut self, line: &[u8], mut pos: usize) -> Option<usize> {
+        let value = pos;
+        while pos < line.len() {
+            let rest = &line[pos..];
+            if let Some(delim) = self.string {
+                if rest.starts_with(delim) {
+                    self.string = None;
+                    pos += delim.len();
+                } else if rest[0] == b'\\' && delim[0] == b'"' {
+                    pos += 2;
+                } else {
+                    pos += 1;
+                }
+                continue;
+            }
+            match rest[0] {
+                b'"' | b'\'' => {
+                    let delim: &'static [u8] = match rest {
+                        [b'"', b'"', b'"', ..] => b"\"\"\"",
+                        [b'\'', b'\'', b'\'', ..] => b"'''",
+                        [b'"', ..] => b"\"",
+                        _ => b"'",
+                    };
+                    self.string = Some(delim);
+                    pos += delim.len();
+                    continue;
+                }
+                b'[' | b'{' => self.depth += 1,
+                b']' | b'}' => self.depth = self.depth.saturating_sub(1),
+                b'#' if pos == value || line[pos - 1] == b' ' || line[pos - 1] == b'\t' => {
+                    return Some(pos);
+                }
+                _ => {}
+            }
+            pos += 1;
+        }
+        if let Some(b"\"" | b"'") = self.string {
+            self.string = None;
+        }
+        None
+    }
+}
+
+/// Returns the position of the first `=` outside of quoted keys.
+fn find_eq(line: &[u8]) -> Option<usize> {
+    let mut quote = None;
+    for (i, &c) in line.iter().enumerate() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == b'"' || c == b'\'' => quote = Some(c),
+            None if c == b'=' => return Some(i),
+            None => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses the lines and returns the cells of each line, `None` for lines printed as
+    /// is, and the section of each line.
+    fn parse(lines: &[&str]) -> Vec<(Option<Vec<String>>, usize)> {
+        let mut ini = Ini::default();
+        let mut res = Vec::new();
+        for line in lines {
+            let (words, section) = ini.parse(line.to_string().into());
+            let cells = (!words.verbatim).then(|| words.iter().map(String::from).collect());
+            res.push((cells, section));
+        }
+        res
+    }
+
+    fn cells(cells: &[&str]) -> Option<Vec<String>> {
+        Some(cells.iter().map(|c| c.to_string()).collect())
+    }
+
+    #[test]
+    fn keys() {
+        assert_eq!(find_eq(b"a = 1"), Some(2));
+        assert_eq!(find_eq(br#""a=b" = 1"#), Some(6));
+        assert_eq!(find_eq(b"'a=b'"), None);
+        assert_eq!(find_eq(b"a"), None);
+    }
+
+    #[test]
+    fn sections() {
+        let lines = parse(&[
+            "name = x # the name",
+            "# a comment",
+            "[server]",
+            "host = 'a # b'",
+            "empty =",
+            "list = [",
+            "  1, 2,",
+            "]",
+            "text = \"\"\"",
+            "a = b",
+            "\"\"\"",
+            "port=80",
+        ]);
+        assert_eq!(lines[0], (cells(&["name", "=", "x", "# the name"]), 0));
+        assert_eq!(lines[1], (None, 0));
+        assert_eq!(lines[2], (None, 1));
+        assert_eq!(lines[3], (cells(&["host", "=", "'a # b'"]), 1));
+        assert_eq!(lines[4], (cells(&["empty", "="]), 1));
+        assert_eq!(lines[5], (cells(&["list", "=", "["]), 1));
+        // Continuation lines of values spanning multiple lines are printed as is.
+        assert_eq!(lines[6], (None, 1));
+        assert_eq!(lines[7], (None, 1));
+        assert_eq!(lines[8], (cells(&["text", "=", "\"\"\""]), 1));
+        assert_eq!(lines[9], (None, 1));
+        assert_eq!(lines[10], (None, 1));
+        assert_eq!(lines[11], (cells(&["port", "=", "80"]), 1));
+    }
+}
@@ -0,0 +1,79 @@
+use {
+    crate::{Alignment::Right, Input, Positioning},
+    std::net::{Ipv4Addr, Ipv6Addr},
+};
+
+#[derive(Default)]
+struct Column {
+    /// Whether a cell is not an IP address.
+    invalid: bool,
+    v4: bool,
+    v6: bool,
+    /// The width of the widest part before `::` of the IPv6 addresses.
+    head: usize,
+}
+
+/// An IP address, optionally followed by a prefix length as in `10.0.0.0/8`.
+enum Address {
+    V4,
+    /// The part of an IPv6 address before `::`, if it contains `::`.
+    V6(Option<usize>),
+}
+
+fn parse(s: &str) -> Option<Address> {
+    let (addr, prefix) = s.split_once('/').unwrap_or((s, "0"));
+    prefix.parse::<u8>().ok()?;
+    if addr.parse::<Ipv4Addr>().is_ok() {
+        return Some(Address::V4);
+    }
+    addr.parse::<Ipv6Addr>().ok()?;
+    Some(Address::V6(addr.find("::")))
+}
+
+/// Aligns the columns of IP addresses.
+///
+/// Columns of IPv4 addresses are right aligned. In columns containing IPv6 addresses, the
+/// addresses are padded so that their `::` line up.
+pub fn normalize(positioning: &mut Positioning, inputs: &mut [Input]) {
+    let mut columns: Vec<Column> = Vec::new();
+    let lines = inputs.iter().flat_map(|i| &i.lines);
+    for line in lines.filter(|l| !l.header && l.rule.is_none()) {
+        for (i, word) in line.words.iter().enumerate() {
+            if columns.len() <= i {
+                columns.resize_with(i + 1, Default::default);
+            }
+            let column = &mut columns[i];
+            match parse(word) {
+                Some(Address::V4) => column.v4 = true,
+                Some(Address::V6(head)) => {
+                    column.v6 = true;
+                    column.head = column.head.max(head.unwrap_or(0));
+                }
+                None if word.is_empty() => {}
+                None => column.invalid = true,
+            }
+        }
+    }
+    // Explicit alignments take precedence.
+    let explicit = positioning.align.vec.len();
+    for (i, column) in columns.iter().enumerate().skip(explicit) {
+        if column.v4 && !column.v6 && !column.invalid {
+            positioning.align.set(i, Right);
+        }
+    }
+    for line in inputs.iter_mut().flat_map(|i| &mut i.lines) {
+        if line.header || line.rule.is_some() {
+            continue;
+        }
+        line.words.map(|i, word| {
+            let column = &columns[i];
+            if !column.v6 || column.invalid {
+                return None;
+            }
+            let Address::V6(Some(head)) = parse(word)? else {
+                return None;
+            };
+            Some(format!("{}{word}", " ".repeat(column.head - head)))
+        });
+    }
+}
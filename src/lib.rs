@@ -0,0 +1,3631 @@
+#![allow(clippy::len_zero, clippy::manual_range_contains)]
+
+use {
+    crate::{
+        diff::Diff, editorconfig::EditorConfig, ini::Ini, log::warning, mapped::LineText, sql::Sql,
+        yaml::Yaml,
+    },
+    serde::{Deserialize, Deserializer, Serialize, Serializer},
+    std::{
+        borrow::Cow,
+        collections::HashMap,
+        fmt::{self, Display, Formatter},
+        io::{BufRead, Write},
+        num::NonZeroUsize,
+        path::Path,
+        sync::Arc,
+    },
+};
+
+mod anchor;
+mod ansi;
+#[cfg(feature = "tokio")]
+mod async_io;
+#[cfg(feature = "cli")]
+mod cli;
+#[cfg(feature = "cli")]
+mod clipboard;
+mod color;
+mod decompress;
+mod detect;
+mod diff;
+mod editorconfig;
+mod encoding;
+mod env;
+mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "cli")]
+mod follow;
+mod hex;
+mod hook;
+mod indent;
+mod infer;
+mod ini;
+mod ip;
+#[cfg(feature = "cli")]
+mod limits;
+mod log;
+mod mapped;
+mod markdown;
+#[cfg(feature = "cli")]
+mod meta;
+#[cfg(feature = "cli")]
+mod pager;
+mod printf;
+mod round;
+#[cfg(feature = "cli")]
+mod sample;
+mod sort;
+mod sql;
+#[cfg(feature = "cli")]
+mod state;
+mod table;
+mod template;
+mod timestamp;
+#[cfg(feature = "cli")]
+mod timings;
+mod totals;
+#[cfg(feature = "wasm")]
+mod wasm;
+mod width;
+mod wrap;
+mod yaml;
+
+#[cfg(feature = "tokio")]
+pub use crate::async_io::align_stream_async;
+pub use crate::{
+    anchor::Occurrence,
+    color::ColorRule,
+    decompress::Decompress,
+    encoding::InputEncoding,
+    error::AlignError,
+    hex::HexCase,
+    hook::CellCommand,
+    log::Diagnostic,
+    printf::ColumnFormat,
+    round::Rounding,
+    sort::SortKey,
+    template::Template,
+    timestamp::TimestampFormat,
+    totals::Stat,
+    width::{Measure, WidthMeasure},
+    wrap::ColumnWrap,
+};
+#[cfg(feature = "cli")]
+pub use crate::{
+    cli::{run, Cli},
+    follow::Widen,
+};
+
+use Alignment::{Centered, Decimal, Justified, Left, Path as PathAlign, Right};
+
+/// Implements `Serialize` and `Deserialize` for a type with a string representation.
+macro_rules! string_serde {
+    ($ty:ty, $parse:expr) => {
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let s = String::deserialize(deserializer)?;
+                $parse(&s).map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+pub(crate) use string_serde;
+
+/// A vector whose elements past the end have a default value.
+#[derive(Clone, Debug)]
+pub struct DynVec<T> {
+    vec: Vec<T>,
+    default: T,
+}
+
+impl<T: Copy + Clone> DynVec<T> {
+    pub fn new(default: T) -> DynVec<T> {
+        DynVec {
+            vec: Vec::new(),
+            default,
+        }
+    }
+
+    pub fn get(&self, i: usize) -> T {
+        if i < self.vec.len() {
+            self.vec[i]
+        } else {
+            self.default
+        }
+    }
+
+    pub fn set(&mut self, index: usize, v: T) {
+        let l = self.vec.len();
+        if index >= l {
+            self.vec.resize(index + 1, self.default);
+        }
+        self.vec[index] = v
+    }
+
+    /// Appends an element, which also becomes the default.
+    pub fn push(&mut self, v: T) {
+        self.default = v;
+        self.vec.push(v);
+    }
+
+    /// The elements before the default.
+    pub fn as_slice(&self) -> &[T] {
+        &self.vec
+    }
+
+    /// The value of all elements past the end.
+    pub fn default_value(&self) -> T {
+        self.default
+    }
+}
+
+impl<T: Copy + PartialEq> DynVec<T> {
+    /// The number of elements up to the last one that differs from the default.
+    fn explicit_len(&self) -> usize {
+        let trailing = self.vec.iter().rev().take_while(|&&v| v == self.default);
+        self.vec.len() - trailing.count()
+    }
+}
+
+/// The alignment of a column.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Alignment {
+    Left,
+    Right,
+    Centered,
+    /// Numbers are aligned on their decimal separator.
+    Decimal,
+    /// Paths are aligned on their last `/`. The directories are right aligned and the file
+    /// names left aligned.
+    Path,
+    /// The padding of the cell is distributed evenly across the gaps between the cells of
+    /// the line.
+    Justified,
+}
+
+/// The widths and alignments of the columns.
+#[derive(Clone, Debug)]
+pub struct Positioning {
+    max_width: DynVec<usize>,
+    align: DynVec<Alignment>,
+    /// The maximum widths. Wider cells are truncated.
+    limit: DynVec<usize>,
+    /// Whether numbers are padded with zeros instead of spaces.
+    zero: DynVec<bool>,
+    /// The width of the widest integer part of decimally aligned columns, or of the
+    /// widest directory of path columns.
+    int_width: DynVec<usize>,
+    /// The width of the widest fractional part, including the decimal separator, of
+    /// decimally aligned columns, or of the widest file name of path columns.
+    frac_width: DynVec<usize>,
+}
+
+impl Default for Positioning {
+    fn default() -> Self {
+        Self {
+            max_width: DynVec::new(0),
+            align: DynVec::new(Left),
+            limit: DynVec::new(usize::MAX),
+            zero: DynVec::new(false),
+            int_width: DynVec::new(0),
+            frac_width: DynVec::new(0),
+        }
+    }
+}
+
+/// The directives accepted in a positioning spec.
+const DIRECTIVES: &str = "\
+Each column is described by an optional minimum width followed by one of
+    <  left aligned
+    >  right aligned
+    =  centered
+    .  numbers aligned on their decimal separator
+    /  paths aligned on their last /
+    %  justified: the padding is spread evenly across the gaps of the line
+A width starting with 0, as in 08>, 00>, or 05-10>, pads numbers in right aligned
+columns with zeros.
+A width range, as in 10-40< or 0-40<, also truncates cells to the maximum width.";
+
+/// Creates an error pointing at the byte `offset` of the positioning `spec`.
+fn spec_error(spec: &str, offset: usize, msg: impl Display) -> AlignError {
+    AlignError::Spec {
+        spec: spec.to_string(),
+        offset,
+        message: msg.to_string(),
+    }
+}
+
+impl Positioning {
+    /// Starts building a positioning at column `i`. Columns are numbered starting at 0.
+    ///
+    /// For example, `Positioning::column(1).right().min_width(20).build()` returns the
+    /// positioning `<20><`.
+    pub fn column(i: usize) -> ColumnBuilder {
+        Positioning::default().into_column(i)
+    }
+
+    /// Continues building this positioning at column `i`.
+    pub fn into_column(self, i: usize) -> ColumnBuilder {
+        ColumnBuilder {
+            positioning: self,
+            column: i,
+        }
+    }
+
+    /// The alignment of column `i`.
+    pub fn alignment(&self, i: usize) -> Alignment {
+        self.align.get(i)
+    }
+
+    /// The minimum width of column `i`.
+    pub fn min_width(&self, i: usize) -> usize {
+        self.max_width.get(i)
+    }
+
+    /// The maximum width of column `i`, if any.
+    pub fn max_width(&self, i: usize) -> Option<usize> {
+        Some(self.limit.get(i)).filter(|&w| w != usize::MAX)
+    }
+
+    /// Whether numbers in column `i` are padded with zeros.
+    pub fn zero_padded(&self, i: usize) -> bool {
+        self.zero.get(i)
+    }
+
+    /// The alignments of the columns.
+    pub fn alignments(&self) -> &DynVec<Alignment> {
+        &self.align
+    }
+
+    /// The minimum widths of the columns.
+    pub fn min_widths(&self) -> &DynVec<usize> {
+        &self.max_width
+    }
+}
+
+/// Renders the positioning as a spec.
+impl Display for Positioning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let column = |f: &mut Formatter<'_>, align, width, limit, zero| {
+            if zero {
+                f.write_str("0")?;
+            }
+            // A range needs its minimum so that the spec does not start with `-`, and zero
+            // padding needs it so that the `0` is not read as the minimum.
+            if width > 0 || limit != usize::MAX || zero {
+                write!(f, "{width}")?;
+            }
+            if limit != usize::MAX {
+                write!(f, "-{limit}")?;
+            }
+            let c = match align {
+                Left => '<',
+                Right => '>',
+                Centered => '=',
+                Decimal => '.',
+                PathAlign => '/',
+                Justified => '%',
+            };
+            write!(f, "{c}")
+        };
+        let n = [
+            self.align.explicit_len(),
+            self.max_width.explicit_len(),
+            self.limit.explicit_len(),
+            self.zero.explicit_len(),
+        ];
+        let n = n.into_iter().max().unwrap();
+        for i in 0..n {
+            column(
+                f,
+                self.align.get(i),
+                self.max_width.get(i),
+                self.limit.get(i),
+                self.zero.get(i),
+            )?;
+        }
+        // Parsing the columns so far implies these values for the following columns.
+        let implied = match n.checked_sub(1) {
+            Some(last) => (
+                self.align.get(last),
+                0,
+                self.limit.get(last),
+                self.zero.get(last),
+            ),
+            None => (Left, 0, usize::MAX, false),
+        };
+        let default = (
+            self.align.default,
+            self.max_width.default,
+            self.limit.default,
+            self.zero.default,
+        );
+        if default != implied {
+            column(f, default.0, default.1, default.2, default.3)?;
+        }
+        Ok(())
+    }
+}
+
+/// Builds a [`Positioning`] one column at a time.
+pub struct ColumnBuilder {
+    positioning: Positioning,
+    column: usize,
+}
+
+impl ColumnBuilder {
+    fn align(mut self, align: Alignment) -> Self {
+        self.positioning.align.set(self.column, align);
+        self
+    }
+
+    pub fn left(self) -> Self {
+        self.align(Left)
+    }
+
+    pub fn right(self) -> Self {
+        self.align(Right)
+    }
+
+    pub fn centered(self) -> Self {
+        self.align(Centered)
+    }
+
+    /// Aligns numbers on their decimal separator.
+    pub fn decimal(self) -> Self {
+        self.align(Decimal)
+    }
+
+    /// Aligns paths on their last `/`.
+    pub fn path(self) -> Self {
+        self.align(PathAlign)
+    }
+
+    /// Distributes the padding of the cells across the gaps between the cells.
+    pub fn justified(self) -> Self {
+        self.align(Justified)
+    }
+
+    pub fn min_width(mut self, width: usize) -> Self {
+        self.positioning.max_width.set(self.column, width);
+        self
+    }
+
+    /// Truncates cells that are wider than `width`.
+    pub fn max_width(mut self, width: usize) -> Self {
+        self.positioning.limit.set(self.column, width);
+        self
+    }
+
+    /// Pads numbers with zeros instead of spaces. This requires right alignment.
+    pub fn zero_padded(mut self) -> Self {
+        self.positioning.zero.set(self.column, true);
+        self.right()
+    }
+
+    /// Continues with column `i`.
+    pub fn column(self, i: usize) -> Self {
+        self.positioning.into_column(i)
+    }
+
+    /// Returns the positioning, or an error if the minimum width of a column exceeds its
+    /// maximum width.
+    pub fn build(self) -> Result<Positioning, AlignError> {
+        let p = &self.positioning;
+        let columns = p.max_width.vec.len().max(p.limit.vec.len()) + 1;
+        // The last index stands for the columns past the end.
+        if (0..columns).any(|i| p.max_width.get(i) > p.limit.get(i)) {
+            let msg = "The maximum width is smaller than the minimum width";
+            return Err(AlignError::Value(msg.to_string()));
+        }
+        Ok(self.positioning)
+    }
+}
+
+impl TryFrom<ColumnBuilder> for Positioning {
+    type Error = AlignError;
+
+    fn try_from(builder: ColumnBuilder) -> Result<Self, AlignError> {
+        builder.build()
+    }
+}
+
+string_serde!(Positioning, parse_positioning);
+
+/// Parses a positioning spec such as `<10>=`.
+pub fn parse_positioning(spec: &str) -> Result<Positioning, AlignError> {
+    let mut align = DynVec::new(Left);
+    let mut max_width = DynVec::new(0);
+    let mut limit = DynVec::new(usize::MAX);
+    let mut zero = DynVec::new(false);
+    let mut pos = 0;
+    let width = |pos: usize, digits: &str| -> Result<usize, AlignError> {
+        digits
+            .parse()
+            .map_err(|e| spec_error(spec, pos, format!("Invalid width: {e}")))
+    };
+    while pos < spec.len() {
+        let fmt = &spec[pos..];
+        let digits = |s: &str| s.bytes().position(|c| !c.is_ascii_digit());
+        let Some(mut non_digit) = digits(fmt) else {
+            return Err(spec_error(spec, spec.len(), "Missing alignment character"));
+        };
+        max_width.push(match non_digit {
+            0 => 0,
+            _ => width(pos, &fmt[..non_digit])?,
+        });
+        // A lone `0`, as in `0<` or `0-6<`, is a minimum width of 0.
+        let padded = fmt.starts_with('0') && non_digit > 1;
+        if fmt[non_digit..].starts_with('-') {
+            let start = non_digit + 1;
+            let Some(len) = digits(&fmt[start..]) else {
+                return Err(spec_error(spec, spec.len(), "Missing alignment character"));
+            };
+            let max = width(pos + start, &fmt[start..start + len])?;
+            if max < max_width.default_value() {
+                let msg = "The maximum width is smaller than the minimum width";
+                return Err(spec_error(spec, pos + start, msg));
+            }
+            limit.push(max);
+            non_digit = start + len;
+        } else {
+            limit.push(usize::MAX);
+        }
+        let c = fmt[non_digit..].chars().next().unwrap();
+        zero.push(padded);
+        if padded && c != '>' {
+            let msg = "Zero padding requires right alignment";
+            return Err(spec_error(spec, pos + non_digit, msg));
+        }
+        match c {
+            '<' => align.push(Left),
+            '>' => align.push(Right),
+            '=' => align.push(Centered),
+            '.' => align.push(Decimal),
+            '/' => align.push(PathAlign),
+            '%' => align.push(Justified),
+            _ => {
+                let msg = format!("Invalid format character `{c}`");
+                return Err(spec_error(spec, pos + non_digit, msg));
+            }
+        }
+        pos += non_digit + 1;
+    }
+    max_width.push(0);
+    Ok(Positioning {
+        max_width,
+        align,
+        limit,
+        zero,
+        ..Default::default()
+    })
+}
+
+/// What to do with blank lines.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+pub enum Blank {
+    /// Keep all blank lines.
+    Keep,
+    /// Remove all blank lines.
+    Drop,
+    /// Replace runs of blank lines by a single blank line.
+    Collapse,
+    /// Keep all blank lines, including their spaces and tabs.
+    Verbatim,
+}
+
+/// What to do with cells that are wider than their column.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+pub enum Overflow {
+    /// Shift the following cells to the right.
+    Push,
+    /// Cut the cell to the width of its column.
+    Truncate,
+}
+
+/// Which part of a truncated cell is removed.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+pub enum TruncateAt {
+    /// Keep the start of the cell.
+    End,
+    /// Keep the end of the cell, as for paths.
+    Start,
+    /// Keep the start and the end of the cell, as for URLs.
+    Middle,
+}
+
+impl TruncateAt {
+    /// The name of the position in the options.
+    fn name(self) -> &'static str {
+        match self {
+            TruncateAt::End => "end",
+            TruncateAt::Start => "start",
+            TruncateAt::Middle => "middle",
+        }
+    }
+}
+
+/// The part of the truncated cells of a column that is removed.
+#[derive(Copy, Clone, Debug)]
+pub struct ColumnTruncation {
+    /// The column, starting at 1.
+    pub column: NonZeroUsize,
+    pub at: TruncateAt,
+}
+
+impl Display for ColumnTruncation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.column, self.at.name())
+    }
+}
+
+string_serde!(ColumnTruncation, parse_column_truncation);
+
+/// Parses a column and a position such as `3:middle`.
+pub fn parse_column_truncation(s: &str) -> Result<ColumnTruncation, AlignError> {
+    let invalid = || AlignError::Value(format!("Expected `column:position`, found `{s}`"));
+    let (column, at) = s.split_once(':').ok_or_else(invalid)?;
+    let at = [TruncateAt::End, TruncateAt::Start, TruncateAt::Middle]
+        .into_iter()
+        .find(|p| p.name().eq_ignore_ascii_case(at))
+        .ok_or_else(invalid)?;
+    Ok(ColumnTruncation {
+        column: column.parse().map_err(|_| invalid())?,
+        at,
+    })
+}
+
+/// Where the cells of a row are placed relative to its tallest cell.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+pub enum VAlign {
+    Top,
+    Middle,
+    Bottom,
+}
+
+/// What to do with lines that are longer than `--max-line-bytes`.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+pub enum LongLines {
+    /// Stop with an error.
+    Error,
+    /// Remove the line.
+    Skip,
+    /// Cut the line to the maximum length.
+    Truncate,
+}
+
+/// How header cells are emphasized.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+pub enum HeaderStyle {
+    /// Convert the cells to upper case.
+    Upper,
+    /// Print the cells in bold.
+    Bold,
+}
+
+/// Which inputs share column widths.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+pub enum WidthScope {
+    /// The columns of all inputs have the same widths.
+    Shared,
+    /// The column widths of each input are computed separately.
+    PerFile,
+}
+
+/// An inclusive range of line or column numbers, starting at 1.
+#[derive(Copy, Clone, Debug)]
+pub struct LineRange {
+    first: usize,
+    last: usize,
+}
+
+impl LineRange {
+    fn contains(&self, line: usize) -> bool {
+        self.first <= line && line <= self.last
+    }
+}
+
+impl Display for LineRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.first > 1 {
+            write!(f, "{}", self.first)?;
+        }
+        if self.first != self.last {
+            f.write_str("-")?;
+            if self.last < usize::MAX {
+                write!(f, "{}", self.last)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+string_serde!(LineRange, parse_line_range);
+
+fn parse_line_range(s: &str) -> Result<LineRange, AlignError> {
+    parse_range(s, "line")
+}
+
+/// Parses a range of line or column numbers, as named by `unit`.
+fn parse_range(s: &str, unit: &str) -> Result<LineRange, AlignError> {
+    let (first, last) = s.split_once('-').unwrap_or((s, s));
+    let parse = |n: &str, default| match n {
+        "" => Ok(default),
+        _ => match n.parse::<usize>() {
+            Ok(0) => Err(AlignError::Value(format!(
+                "{}{} numbers start at 1",
+                unit[..1].to_uppercase(),
+                &unit[1..]
+            ))),
+            Ok(n) => Ok(n),
+            Err(e) => Err(AlignError::Value(format!(
+                "Invalid {unit} number `{n}`: {e}"
+            ))),
+        },
+    };
+    let range = LineRange {
+        first: parse(first, 1)?,
+        last: parse(last, usize::MAX)?,
+    };
+    if range.first > range.last {
+        let msg = format!("The first {unit} must not come after the last {unit}");
+        return Err(AlignError::Value(msg));
+    }
+    Ok(range)
+}
+
+/// The string delimiter of a column.
+#[derive(Copy, Clone, Debug)]
+pub struct ColumnDelim {
+    /// The column, starting at 1.
+    pub column: NonZeroUsize,
+    pub delim: char,
+}
+
+impl Display for ColumnDelim {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.column, self.delim)
+    }
+}
+
+string_serde!(ColumnDelim, parse_column_delim);
+
+fn parse_column_delim(s: &str) -> Result<ColumnDelim, AlignError> {
+    let invalid = || AlignError::Value(format!("Expected `column=delimiter`, found `{s}`"));
+    let (column, delim) = s.split_once('=').ok_or_else(invalid)?;
+    let mut chars = delim.chars();
+    let (Some(delim), None) = (chars.next(), chars.next()) else {
+        return Err(invalid());
+    };
+    Ok(ColumnDelim {
+        column: column.parse().map_err(|_| invalid())?,
+        delim,
+    })
+}
+
+/// The options of the alignment.
+///
+/// Most options correspond to the command line arguments of the same name. When
+/// deserialized, missing options take their default values.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::Args))]
+#[serde(default, rename_all = "kebab-case")]
+pub struct AlignConfig {
+    /// The output separator.
+    ///
+    /// By default, columns are separated by a space.
+    #[cfg_attr(feature = "cli", arg(short = 'o', value_name = "output separator", default_value_t = {" ".to_string()}, hide_default_value = true))]
+    pub out_sep: String,
+    /// Separate the cells by the text that separated them in the input.
+    ///
+    /// Only the padding of the cells changes.
+    #[cfg_attr(feature = "cli", arg(long, conflicts_with = "out_sep"))]
+    pub keep_sep: bool,
+    /// The string delimiter.
+    ///
+    /// By default, strings are delimited by `"`.
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            short = 's',
+            value_name = "string delimiter",
+            default_value_t = '"',
+            hide_default_value = true
+        )
+    )]
+    pub str_delim: char,
+    /// The string delimiter of a column.
+    ///
+    /// Can be given multiple times. Quotes in all other columns are then ordinary
+    /// characters.
+    ///
+    /// Example: 3='
+    #[cfg_attr(feature = "cli", arg(long, value_name = "column=delimiter", value_parser = parse_column_delim))]
+    pub string_delim: Vec<ColumnDelim>,
+    /// The field separator.
+    ///
+    /// Spaces and tabs around each field are removed.
+    ///
+    /// By default, fields are separated by runs of spaces and tabs.
+    #[cfg_attr(feature = "cli", arg(short = 'F', value_name = "field separator"))]
+    pub field_sep: Option<String>,
+    /// Keep empty fields.
+    ///
+    /// By default, empty fields between field separators are removed.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub keep_empty_fields: bool,
+    /// The placeholder for empty cells.
+    ///
+    /// Empty fields and the missing cells at the end of lines with fewer cells than the
+    /// other lines of their block are replaced by the placeholder, which is aligned like
+    /// any other cell.
+    ///
+    /// Example: -
+    #[cfg_attr(feature = "cli", arg(long, value_name = "placeholder"))]
+    pub empty: Option<String>,
+    /// Split fields only at tabs and runs of two or more spaces.
+    ///
+    /// This recovers the columns of already aligned output, such as that of `docker ps`,
+    /// whose cells contain single spaces.
+    #[cfg_attr(feature = "cli", arg(long, conflicts_with = "field_sep"))]
+    pub two_space: bool,
+    /// Remove the string delimiters around quoted cells.
+    ///
+    /// Escaped delimiters inside the cells are unescaped.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub unquote: bool,
+    /// Quote cells that contain whitespace.
+    ///
+    /// Delimiters inside the cells are escaped with a backslash. Cells that are already
+    /// quoted are not changed. Together with --unquote, the quoting is normalized.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub requote: bool,
+    /// Collapse runs of whitespace inside cells to a single space.
+    ///
+    /// Whitespace inside strings is kept.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub squeeze: bool,
+    /// Guess the field separator and whether there is a header from the input.
+    ///
+    /// Tabs, runs of two or more spaces, commas, pipes, and semicolons are considered. If
+    /// none of them consistently splits the input into multiple fields, the default
+    /// separator is used. The first line is a header if none of its cells is a number
+    /// while some column below it contains only numbers.
+    #[cfg_attr(feature = "cli", arg(long, conflicts_with = "field_sep"))]
+    pub detect: bool,
+    /// Read and write pipe-separated values.
+    ///
+    /// Fields are separated by `|` and empty fields are kept. Pipes escaped with a
+    /// backslash are part of the field. The columns are separated by ` | `.
+    #[cfg_attr(feature = "cli", arg(long, conflicts_with_all = ["field_sep", "two_space", "detect", "out_sep", "keep_sep"]))]
+    pub psv: bool,
+    /// Align the `=` of `KEY=value` assignments.
+    ///
+    /// Each assignment is split into the key, including an `export` prefix, the `=`, the
+    /// value, and a trailing comment. Values are never split, and all other lines,
+    /// including the bodies of here-documents, are printed as is.
+    #[cfg_attr(feature = "cli", arg(long, group = "mode", conflicts_with_all = ["field_sep", "two_space", "detect"]))]
+    pub env: bool,
+    /// Align the lines on a token.
+    ///
+    /// Each line is split at the token, which becomes a cell of its own. Tokens inside
+    /// strings are ignored, and lines without the token are printed as is.
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        value_name = "token",
+        value_parser = clap::builder::NonEmptyStringValueParser::new(),
+        group = "mode",
+        conflicts_with_all = ["field_sep", "two_space", "detect"]
+    ))]
+    pub on: Option<String>,
+    /// The occurrences of the --on token at which lines are split.
+    ///
+    /// Either a number, starting at 1, or `*` for every occurrence.
+    ///
+    /// By default, lines are split at the first occurrence.
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        value_name = "n",
+        value_parser = anchor::parse_occurrence,
+        default_value = "1",
+        hide_default_value = true,
+        requires = "on"
+    ))]
+    pub occurrence: Occurrence,
+    /// A further token at which the rest of the line is split after the --on token.
+    ///
+    /// Can be given multiple times to split at several tokens in order. Each token gets a
+    /// column of its own, even in lines that do not contain it.
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        value_name = "token",
+        value_parser = clap::builder::NonEmptyStringValueParser::new(),
+        requires = "on"
+    ))]
+    pub then: Vec<String>,
+    /// Align the values of YAML mappings.
+    ///
+    /// The values of sibling keys are aligned after the `:`. Each line keeps its
+    /// indentation. Values, block scalars, and comments are never split or changed.
+    #[cfg_attr(feature = "cli", arg(long, group = "mode", conflicts_with_all = ["field_sep", "two_space", "detect", "group_by", "blocks", "auto_blocks"]))]
+    pub yaml: bool,
+    /// Align the `=` of TOML and INI assignments.
+    ///
+    /// The assignments of each section are aligned separately. Each assignment is split
+    /// into the key, the `=`, the value, and a trailing comment. Each line keeps its
+    /// indentation. Values are never split, and all other lines, including the
+    /// continuation lines of arrays and strings spanning multiple lines, are printed as
+    /// is.
+    #[cfg_attr(feature = "cli", arg(long, group = "mode", conflicts_with_all = ["field_sep", "two_space", "detect", "group_by", "blocks", "auto_blocks"]))]
+    pub ini: bool,
+    /// Align the column definitions and tuples of SQL statements.
+    ///
+    /// The column definitions of `CREATE TABLE` statements are split into the name, the
+    /// type, and the constraints. The tuples of `INSERT ... VALUES` statements are split
+    /// into their values. Each statement is aligned separately and each line keeps its
+    /// indentation. All other lines are printed as is.
+    #[cfg_attr(feature = "cli", arg(long, group = "mode", conflicts_with_all = ["field_sep", "two_space", "detect", "group_by", "blocks", "auto_blocks"]))]
+    pub sql: bool,
+    /// Align the lines of the hunks of a unified diff.
+    ///
+    /// The first character of each line, `+`, `-`, or a space, is kept as is and the rest
+    /// of the line is aligned. All lines outside of hunks are printed as is.
+    #[cfg_attr(feature = "cli", arg(long, group = "mode"))]
+    pub diff: bool,
+    /// Realign a table drawn with `|`, `+`, and `-` or with box-drawing characters.
+    ///
+    /// The cells are recovered from the vertical bars, and the frame is redrawn with `|`,
+    /// `+`, and `-` at the new column widths. Borders, such as those printed by mysql and
+    /// psql, are redrawn where they were. With -o, --to-markdown, or --template, the
+    /// borders are removed instead. Lines without a vertical bar are printed as is.
+    #[cfg_attr(feature = "cli", arg(long, group = "mode", conflicts_with_all = ["field_sep", "two_space", "detect", "psv"]))]
+    pub table: bool,
+    /// Read Markdown tables.
+    ///
+    /// Rows are split at the pipes that are not escaped with a backslash. The alignments
+    /// of the delimiter row apply to the columns whose alignment is not given by the
+    /// positioning. Lines without a pipe are printed as is.
+    #[cfg_attr(feature = "cli", arg(long, group = "mode", conflicts_with_all = ["field_sep", "two_space", "detect", "psv"]))]
+    pub from_markdown: bool,
+    /// Write Markdown tables.
+    ///
+    /// Each row is enclosed in pipes and a delimiter row with the alignments of the
+    /// columns is written below the first row. Pipes inside cells are escaped. Together
+    /// with --from-markdown, Markdown tables are realigned.
+    #[cfg_attr(feature = "cli", arg(long, conflicts_with_all = ["out_sep", "keep_sep", "psv", "flush_right_last"]))]
+    pub to_markdown: bool,
+    /// The maximum column.
+    ///
+    /// By default, there is no maximum.
+    #[cfg_attr(feature = "cli", arg(short = 'u', value_name = "until", default_value_t = usize::MAX, hide_default_value = true))]
+    pub until: usize,
+    /// The number of columns.
+    ///
+    /// Lines are split into at most this many cells. The last cell contains the rest of
+    /// the line, including any separators.
+    #[cfg_attr(feature = "cli", arg(long, value_name = "n", conflicts_with = "until"))]
+    pub columns: Option<NonZeroUsize>,
+    /// The positioning of the columns.
+    ///
+    /// By default, all columns are left aligned.
+    ///
+    /// Example: <50>=<{n}
+    /// {n}- The first column is left aligned
+    /// {n}- The second column is right aligned and has a minimum width of 50
+    /// {n}- The third column is centered
+    /// {n}- The fourth and all following columns are left aligned
+    #[cfg_attr(feature = "cli", arg(value_parser = parse_positioning, default_value = "", hide_default_value = true))]
+    pub positioning: Positioning,
+    /// The column whose value groups the lines.
+    ///
+    /// Column widths are computed separately for each run of lines with the same value
+    /// in this column. Columns are numbered starting at 1.
+    ///
+    /// By default, all lines form a single group.
+    #[cfg_attr(feature = "cli", arg(long, value_name = "column"))]
+    pub group_by: Option<NonZeroUsize>,
+    /// Align each block of lines separately.
+    ///
+    /// Blocks are separated by blank lines, which are kept.
+    ///
+    /// By default, all lines form a single group.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub blocks: bool,
+    /// Which inputs share column widths.
+    ///
+    /// With `--yaml`, `--ini`, and `--sql`, the widths are always computed per file.
+    ///
+    /// By default, the columns of all inputs have the same widths.
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            value_name = "scope",
+            default_value = "shared",
+            hide_default_value = true
+        )
+    )]
+    pub widths: WidthScope,
+    /// Align each block of lines of the same shape separately.
+    ///
+    /// Like `--blocks`, but a block also ends where the number of columns or the
+    /// indentation changes.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub auto_blocks: bool,
+    /// Keep the indentation of each line as part of the first column.
+    ///
+    /// The indentation counts toward the width of the first column, so the following
+    /// columns line up across lines of different indentation.
+    ///
+    /// By default, all lines get the indentation of the first line.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub indent_column: bool,
+    /// The indentation of the lines.
+    ///
+    /// Replaces the indentation taken from the first line. `none` removes the
+    /// indentation. Lines that keep their own indentation are not affected.
+    ///
+    /// By default, all lines get the indentation of the first line.
+    #[cfg_attr(feature = "cli", arg(long, value_name = "indent"))]
+    pub indent: Option<String>,
+    /// Align the lines of each indentation separately.
+    ///
+    /// Each line keeps its indentation, and the lines with the same indentation share
+    /// column widths even if lines with other indentations are between them.
+    #[cfg_attr(feature = "cli", arg(long, conflicts_with_all = ["yaml", "ini", "sql", "indent_column"]))]
+    pub by_indent: bool,
+    /// The prefix of continuation lines.
+    ///
+    /// A line starting with this prefix, ignoring indentation, continues the last cell of
+    /// the preceding line. The cell then spans multiple lines in the output.
+    ///
+    /// By default, every line is a separate row.
+    #[cfg_attr(feature = "cli", arg(long, value_name = "prefix"))]
+    pub continuation: Option<String>,
+    /// Continue strings that are still open at the end of a line on the next line.
+    ///
+    /// The lines spanned by a string form a single row, and the string is a single cell
+    /// that spans multiple lines in the output.
+    ///
+    /// By default, each line is a separate row and strings end at the end of the line.
+    #[cfg_attr(feature = "cli", arg(long, conflicts_with_all = ["follow", "sample"]))]
+    pub multiline_strings: bool,
+    /// Continue lines with unclosed brackets on the next lines.
+    ///
+    /// A line with an unclosed `(`, `[`, or `{` outside of strings forms a single row with
+    /// the following lines up to the one that closes the bracket. The text inside the
+    /// bracket is a single cell that keeps its line breaks, and the cells after it are
+    /// aligned on the last line.
+    #[cfg_attr(feature = "cli", arg(long, conflicts_with = "multiline_strings"))]
+    pub join_brackets: bool,
+    /// The handling of blank lines.
+    ///
+    /// Blank lines are empty or consist of spaces and tabs. They do not affect the
+    /// indentation.
+    ///
+    /// By default, blank lines are kept but printed without spaces and tabs.
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            value_name = "policy",
+            default_value = "keep",
+            hide_default_value = true
+        )
+    )]
+    pub blank: Blank,
+    /// Print lines with a single cell as is.
+    ///
+    /// Such lines, like sentences and headings between tables, are neither reindented
+    /// nor padded and do not affect the widths of the columns.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub keep_single: bool,
+    /// Print inputs with fewer than n lines that are not blank as is.
+    ///
+    /// This keeps small selections, such as two lines passed by an editor, from being
+    /// reformatted. Such inputs get neither line numbers nor totals.
+    #[cfg_attr(feature = "cli", arg(long, value_name = "n", conflicts_with_all = ["follow", "sample"]))]
+    pub min_lines: Option<usize>,
+    /// Fail if a row has a different number of cells than the first row.
+    ///
+    /// The error names the line and its cells. Headers and lines printed as is are not
+    /// checked.
+    #[cfg_attr(feature = "cli", arg(long, conflicts_with_all = ["follow", "sample"]))]
+    pub strict: bool,
+    /// The display offsets at which the columns start.
+    ///
+    /// The widths of all but the last of these columns are fixed by the offsets. Offsets
+    /// are counted from the end of the indentation.
+    ///
+    /// Example: 0,20,45
+    #[cfg_attr(
+        feature = "cli",
+        arg(long, value_name = "offsets", value_delimiter = ',')
+    )]
+    pub stops: Vec<usize>,
+    /// The widths of the first columns regardless of their cells.
+    ///
+    /// Cells that are wider than their column overflow according to --overflow.
+    ///
+    /// Example: 12,30,8
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            value_name = "widths",
+            value_delimiter = ',',
+            conflicts_with = "stops"
+        )
+    )]
+    pub fixed_widths: Vec<usize>,
+    /// Size each column to fit this percentage of its cells.
+    ///
+    /// The few cells that are wider than the given percentile of the widths overflow
+    /// according to --overflow instead of widening the whole column. Minimum widths of
+    /// the positioning still apply.
+    ///
+    /// Example: 95
+    #[cfg_attr(feature = "cli", arg(long, value_name = "percent", value_parser = clap::value_parser!(u8).range(1..=100)))]
+    pub width_percentile: Option<u8>,
+    /// The handling of cells that are wider than their column.
+    ///
+    /// Only columns with a fixed width can overflow. Cells that are wider than the maximum
+    /// width of their column are always truncated.
+    ///
+    /// By default, the following cells are pushed to the right as far as necessary.
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            value_name = "policy",
+            default_value = "push",
+            hide_default_value = true
+        )
+    )]
+    pub overflow: Overflow,
+    /// The part of truncated cells that is removed.
+    ///
+    /// By default, the end of the cell is removed.
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            value_name = "position",
+            default_value = "end",
+            hide_default_value = true
+        )
+    )]
+    pub truncate_at: TruncateAt,
+    /// The part of the truncated cells of a column that is removed.
+    ///
+    /// This overrides --truncate-at for the column. The position is one of `end`,
+    /// `start`, and `middle`. Keeping both ends suits columns of URLs and paths.
+    ///
+    /// Example: 3:middle
+    #[cfg_attr(feature = "cli", arg(long, value_name = "column:position", value_parser = parse_column_truncation))]
+    pub truncate_column: Vec<ColumnTruncation>,
+    /// The text that replaces the removed part of truncated cells, such as `…`.
+    ///
+    /// The marker counts toward the width of the cell.
+    ///
+    /// By default, there is no marker.
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            value_name = "marker",
+            default_value = "",
+            hide_default_value = true
+        )
+    )]
+    pub truncate_marker: String,
+    /// Wrap the cells of a column at a width.
+    ///
+    /// Cells are broken into lines at whitespace, and the following lines are printed
+    /// below the cell in the same column. Can be given multiple times. Columns are
+    /// numbered starting at 1.
+    ///
+    /// Since lines are split into cells at whitespace by default, their cells cannot be
+    /// wrapped. Only cells that contain whitespace, such as those split by -F,
+    /// --two-space, --columns, or -u, are wrapped.
+    ///
+    /// Example: 4:60
+    #[cfg_attr(feature = "cli", arg(long, value_name = "column:width", value_parser = wrap::parse_column_wrap))]
+    pub wrap: Vec<ColumnWrap>,
+    /// The vertical alignment of the cells of rows that span multiple lines.
+    ///
+    /// By default, cells are placed at the top of their row.
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            value_name = "position",
+            default_value = "top",
+            hide_default_value = true
+        )
+    )]
+    pub valign: VAlign,
+    /// Pad the last cell of each line so that it ends at the right margin.
+    ///
+    /// This applies to lines with at least two cells. The alignment of the last column
+    /// is ignored.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub flush_right_last: bool,
+    /// The width of the output used by --flush-right-last.
+    ///
+    /// By default, the width of the terminal or, if stdout is not a terminal, the value
+    /// of the COLUMNS environment variable or 80.
+    #[cfg_attr(
+        feature = "cli",
+        arg(long, value_name = "columns", requires = "flush_right_last")
+    )]
+    pub width: Option<usize>,
+    /// Normalize hexadecimal columns.
+    ///
+    /// A column is hexadecimal if all of its cells are hexadecimal numbers, at least one
+    /// of them contains a letter, and either one of them has a `0x` prefix or most of them
+    /// contain only decimal digits. The digits of such columns are converted to the given
+    /// case and padded with zeros to the same number of digits. If any cell has a `0x`
+    /// prefix, all cells get one. The columns are right aligned unless the positioning
+    /// gives their alignment.
+    #[cfg_attr(feature = "cli", arg(long, value_name = "case", num_args = 0..=1, default_missing_value = "lower"))]
+    pub hex: Option<HexCase>,
+    /// Align columns of IP addresses.
+    ///
+    /// A column contains IP addresses if all of its cells are IPv4 or IPv6 addresses,
+    /// optionally followed by a prefix length as in `10.0.0.0/8`. Columns of IPv4
+    /// addresses are right aligned. In columns with IPv6 addresses, the addresses are
+    /// padded so that their `::` line up. The positioning takes precedence over the
+    /// alignment.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub ip: bool,
+    /// Use a comma as the decimal separator.
+    ///
+    /// Dots are then accepted as thousands separators, as in `1.234,56`.
+    ///
+    /// By default, the decimal separator is a dot and commas are thousands separators.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub decimal_comma: bool,
+    /// A printf-style format of the numbers of a column.
+    ///
+    /// The conversions d, i, x, X, o, f, F, e, and E are supported together with the
+    /// flags `-`, `+`, space, `0`, and `#`, a width, and a precision. Cells that are not
+    /// numbers are not changed. Can be given multiple times. Columns are numbered
+    /// starting at 1.
+    ///
+    /// Example: 3=%08.2f
+    #[cfg_attr(feature = "cli", arg(long, value_name = "column=format", value_parser = printf::parse_column_format))]
+    pub fmt: Vec<ColumnFormat>,
+    /// Round the numbers of a column.
+    ///
+    /// Either the number of digits after the decimal separator, as in `4=2`, or the number
+    /// of significant digits, as in `2=3sig`. Cells that are not numbers are not changed.
+    /// Can be given multiple times. Columns are numbered starting at 1.
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        visible_alias = "decimals",
+        value_name = "column=digits",
+        value_parser = round::parse_rounding
+    ))]
+    pub round_to: Vec<Rounding>,
+    /// Treat timestamps as single cells.
+    ///
+    /// Syslog timestamps like `Jan  2 10:00:00` and ISO 8601 timestamps like
+    /// `2024-01-02 10:00:00` are not split at their spaces. The timestamps can optionally
+    /// be converted to ISO 8601.
+    #[cfg_attr(feature = "cli", arg(long, value_name = "format", num_args = 0..=1, default_missing_value = "keep"))]
+    pub timestamps: Option<TimestampFormat>,
+    /// Align the columns according to the types of their cells.
+    ///
+    /// Each column is classified as integer, float, date, boolean, or text from the
+    /// first 1000 rows. Integers are right aligned, floats are aligned on their decimal
+    /// separator, booleans are centered, and dates and text are left aligned. The
+    /// positioning takes precedence. Headers are ignored, so inputs with a header should
+    /// be aligned with --header.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub infer: bool,
+    /// Number the lines.
+    ///
+    /// The numbers are printed in a right aligned column before the indentation. Only
+    /// lines with cells are numbered, except for the header and the rule below it.
+    #[cfg_attr(feature = "cli", arg(long, conflicts_with = "follow"))]
+    pub number: bool,
+    /// The number of the first line.
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            value_name = "n",
+            default_value = "1",
+            hide_default_value = true,
+            requires = "number"
+        )
+    )]
+    pub number_start: usize,
+    /// The format of line numbers, in which `{}` is replaced by the number.
+    ///
+    /// Example: {}.
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            value_name = "format",
+            default_value = "{}",
+            hide_default_value = true,
+            requires = "number"
+        )
+    )]
+    pub number_format: String,
+    /// Append the totals of these columns to each input.
+    ///
+    /// The totals are printed below a rule. Cells that are not numbers are ignored.
+    ///
+    /// Example: 2,4
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        value_name = "columns",
+        value_delimiter = ',',
+        conflicts_with_all = ["yaml", "ini", "sql", "diff", "group_by", "follow"]
+    ))]
+    pub totals: Vec<NonZeroUsize>,
+    /// The statistics printed by --totals.
+    ///
+    /// A comma-separated list of statistics.
+    ///
+    /// By default, only the sums are printed.
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            value_name = "stats",
+            value_delimiter = ',',
+            default_value = "sum",
+            hide_default_value = true,
+            requires = "totals"
+        )
+    )]
+    pub total_stats: Vec<Stat>,
+    /// Print a ruler above the lines of each group of columns.
+    ///
+    /// The ruler shows the number of each column followed by dashes to the width of the
+    /// column, with a `:` every five display columns. This helps to write the
+    /// positioning.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub ruler: bool,
+    /// Print each row by substituting its cells into a template.
+    ///
+    /// `{n}` is replaced by the cell of column n, starting at 1. `{n:<w}`, `{n:>w}`, and
+    /// `{n:^w}` pad the cell to the width w and align it to the left, right, or center.
+    /// Without a width, as in `{n:>}`, the cell is padded to the width of its column.
+    /// `{{` and `}}` are literal braces. Lines without cells are printed as is.
+    ///
+    /// Example: '{1} -> {3} ({2:>8})'
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        value_name = "template",
+        value_parser = template::parse_template,
+        conflicts_with_all = ["to_markdown", "ruler", "keep_sep"]
+    ))]
+    pub template: Option<Template>,
+    /// Color the cells of a column that satisfy a test.
+    ///
+    /// A rule consists of the column, an operator, a value, and a color. The operators
+    /// `>=`, `<=`, `==`, `!=`, `>`, and `<` compare numbers by value and other text
+    /// alphabetically. The operator `~` matches a regular expression. With a `:row`
+    /// suffix, the whole row is colored. Can be given multiple times. The first matching
+    /// rule applies. Header lines are never colored. On the command line, colors are
+    /// subject to --color.
+    ///
+    /// Example: 3>=90:red
+    #[cfg_attr(feature = "cli", arg(long, value_name = "rule", value_parser = color::parse_color_rule))]
+    pub color_if: Vec<ColorRule>,
+    /// The lines to align.
+    ///
+    /// All other lines are printed as is and do not affect the indentation or the column
+    /// widths. Either end of the range can be omitted. Lines are numbered starting at 1.
+    ///
+    /// Example: 120-180
+    ///
+    /// By default, all lines are aligned.
+    #[cfg_attr(feature = "cli", arg(long, value_name = "range", value_parser = parse_line_range, conflicts_with = "follow"))]
+    pub lines: Option<LineRange>,
+    /// Print only the first n lines of each input.
+    ///
+    /// The header is always printed. Together with --tail, the first and the last lines
+    /// are printed.
+    #[cfg_attr(feature = "cli", arg(long, value_name = "n", conflicts_with_all = ["follow", "sample"]))]
+    pub head: Option<usize>,
+    /// Print only the last n lines of each input.
+    ///
+    /// The header is always printed.
+    #[cfg_attr(feature = "cli", arg(long, value_name = "n", conflicts_with_all = ["follow", "sample"]))]
+    pub tail: Option<usize>,
+    /// Compute the column widths from all lines instead of only the printed ones.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub all_widths: bool,
+    /// Print the lines of each input in reverse order.
+    ///
+    /// The header stays at the top.
+    #[cfg_attr(feature = "cli", arg(long, conflicts_with_all = ["follow", "sample"]))]
+    pub reverse: bool,
+    /// Sort the rows by these columns.
+    ///
+    /// A comma-separated list of keys. Each key is a column number, starting at 1,
+    /// optionally followed by `:str` or `:num` and by `:asc` or `:desc`. Later keys order
+    /// the rows that are equal under the earlier ones. Rows that are equal under all keys
+    /// keep their order. Numeric keys put numbers before other cells. Only consecutive rows
+    /// of the same group are sorted, so headers, rules, and blank lines stay in place.
+    ///
+    /// Example: 1:str,3:num:desc
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        value_name = "keys",
+        value_parser = sort::parse_sort_key,
+        value_delimiter = ',',
+        conflicts_with_all = ["follow", "sample"]
+    ))]
+    pub sort_by: Vec<SortKey>,
+    /// The number of leading lines to print as is.
+    ///
+    /// These lines do not affect the indentation or the column widths. This is useful
+    /// for shebangs, license headers, and banners.
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            value_name = "lines",
+            default_value = "0",
+            hide_default_value = true,
+            conflicts_with = "follow"
+        )
+    )]
+    pub skip: usize,
+    /// Treat the first line of each input as a header.
+    ///
+    /// The header cells are centered regardless of the alignment of their columns and do
+    /// not take part in the detection of hexadecimal columns or the alignment of decimal
+    /// separators.
+    #[cfg_attr(feature = "cli", arg(long, conflicts_with = "follow"))]
+    pub header: bool,
+    /// How header cells are emphasized.
+    ///
+    /// A comma-separated list of styles. On the command line, bold is subject to --color.
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            value_name = "styles",
+            value_delimiter = ',',
+            requires = "header"
+        )
+    )]
+    pub header_style: Vec<HeaderStyle>,
+    /// Repeat the header every n rows.
+    ///
+    /// A rule of dashes directly below the header is repeated with it.
+    #[cfg_attr(feature = "cli", arg(long, value_name = "n", requires = "header"))]
+    pub reheader: Option<NonZeroUsize>,
+    /// The maximum length of a line in bytes.
+    ///
+    /// Longer lines are handled according to `--long-lines`.
+    ///
+    /// By default, lines can be arbitrarily long.
+    #[cfg_attr(feature = "cli", arg(long, value_name = "bytes"))]
+    pub max_line_bytes: Option<usize>,
+    /// The handling of lines that are longer than `--max-line-bytes`.
+    ///
+    /// By default, such lines are an error.
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            value_name = "policy",
+            default_value = "error",
+            hide_default_value = true,
+            requires = "max_line_bytes"
+        )
+    )]
+    pub long_lines: LongLines,
+    /// How compressed inputs are recognized.
+    ///
+    /// Gzip, zstd, and xz compressed inputs are decompressed before they are aligned.
+    ///
+    /// By default, files ending in `.gz`, `.zst`, or `.xz` are decompressed.
+    #[cfg_attr(feature = "cli", arg(long, value_name = "mode", num_args = 0..=1, default_missing_value = "auto"))]
+    pub decompress: Option<Decompress>,
+    /// Terminate records with NUL instead of newline.
+    ///
+    /// This applies to both the input and the output.
+    #[cfg_attr(feature = "cli", arg(short = 'z', long))]
+    pub null: bool,
+    /// The separator of the records that become rows.
+    ///
+    /// The input is split into records after each occurrence of the separator, which
+    /// stays part of its record. Line breaks inside records are treated as spaces. An
+    /// empty separator splits the input at blank lines.
+    ///
+    /// By default, each line is a record.
+    ///
+    /// Example: ;
+    #[cfg_attr(feature = "cli", arg(
+        short = 'R',
+        long,
+        value_name = "separator",
+        conflicts_with_all = ["follow", "sample"]
+    ))]
+    pub record_sep: Option<String>,
+    /// The encoding of the input.
+    ///
+    /// The input is decoded before it is split into cells. With `auto`, the encoding is
+    /// detected from the byte order mark. Input without one is UTF-8 if it is valid
+    /// UTF-8 and Windows-1252 otherwise.
+    ///
+    /// Example: utf-16le, latin1, shift_jis, auto
+    ///
+    /// By default, the input is UTF-8.
+    #[cfg_attr(feature = "cli", arg(long, value_name = "encoding", value_parser = encoding::parse_encoding, conflicts_with = "follow"))]
+    pub encoding: Option<InputEncoding>,
+    /// Encode the output in the encoding of the input.
+    ///
+    /// By default, the output is UTF-8.
+    #[cfg_attr(feature = "cli", arg(long, requires = "encoding"))]
+    pub keep_encoding: bool,
+    /// Print a byte order mark before inputs that start with one.
+    ///
+    /// UTF-8 and UTF-16 byte order marks are always detected and removed from the
+    /// input. Input with a UTF-16 byte order mark is decoded as UTF-16.
+    ///
+    /// By default, the byte order mark is dropped.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub keep_bom: bool,
+    /// The tokenizer that splits lines into cells.
+    ///
+    /// By default, the built-in tokenizer configured by these options is used. Modes
+    /// such as `--yaml` use their own tokenizers.
+    #[cfg_attr(feature = "cli", arg(skip))]
+    #[serde(skip)]
+    pub tokenizer: Option<Arc<dyn Tokenizer + Send + Sync>>,
+    /// How the widths of cells are measured.
+    ///
+    /// By default, cells are as wide as they are displayed in a terminal.
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            value_name = "measure",
+            default_value = "unicode",
+            hide_default_value = true
+        )
+    )]
+    pub measure: Measure,
+    /// A custom measure of the widths of cells that takes precedence over `measure`.
+    #[cfg_attr(feature = "cli", arg(skip))]
+    #[serde(skip)]
+    pub width_measure: Option<Arc<dyn WidthMeasure + Send + Sync>>,
+    /// A command that rewrites the cells of a column.
+    ///
+    /// The cells of the column are written to the stdin of the command, one per line,
+    /// and are replaced by the lines it prints. This happens before the widths of the
+    /// columns are computed. Columns are numbered starting at 1. With --sample, the lines
+    /// after the sample are rewritten and printed in batches of the size of the sample.
+    ///
+    /// Example: 2:sed 's/^"\(.*\)"$/\1/'
+    #[cfg_attr(feature = "cli", arg(long, value_name = "column:command", value_parser = hook::parse_cell_command))]
+    pub map_cell: Vec<CellCommand>,
+    /// A function that rewrites cells before the widths of the columns are computed.
+    ///
+    /// It is called with the column, starting at 0, and the cell. If it returns a text,
+    /// the text replaces the cell.
+    #[cfg_attr(feature = "cli", arg(skip))]
+    #[serde(skip)]
+    pub cell_hook: Option<CellHook>,
+}
+
+/// A function that rewrites cells. See [`AlignConfig::cell_hook`].
+pub type CellHook = Arc<dyn CellFn>;
+
+/// The functions that can be used as a [`CellHook`].
+pub trait CellFn: Fn(usize, &str) -> Option<String> + Send + Sync {}
+
+impl<F: Fn(usize, &str) -> Option<String> + Send + Sync> CellFn for F {}
+
+impl fmt::Debug for dyn CellFn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("CellFn")
+    }
+}
+
+impl Default for AlignConfig {
+    fn default() -> Self {
+        Self {
+            out_sep: " ".to_string(),
+            keep_sep: Default::default(),
+            str_delim: '"',
+            string_delim: Default::default(),
+            field_sep: Default::default(),
+            keep_empty_fields: Default::default(),
+            empty: Default::default(),
+            two_space: Default::default(),
+            unquote: Default::default(),
+            requote: Default::default(),
+            squeeze: Default::default(),
+            detect: Default::default(),
+            psv: Default::default(),
+            env: Default::default(),
+            on: Default::default(),
+            occurrence: Occurrence::Nth(NonZeroUsize::MIN),
+            then: Default::default(),
+            yaml: Default::default(),
+            ini: Default::default(),
+            sql: Default::default(),
+            diff: Default::default(),
+            table: Default::default(),
+            from_markdown: Default::default(),
+            to_markdown: Default::default(),
+            until: usize::MAX,
+            columns: Default::default(),
+            positioning: Default::default(),
+            group_by: Default::default(),
+            blocks: Default::default(),
+            widths: WidthScope::Shared,
+            auto_blocks: Default::default(),
+            indent_column: Default::default(),
+            indent: Default::default(),
+            by_indent: Default::default(),
+            continuation: Default::default(),
+            multiline_strings: Default::default(),
+            join_brackets: Default::default(),
+            blank: Blank::Keep,
+            keep_single: Default::default(),
+            min_lines: Default::default(),
+            strict: Default::default(),
+            stops: Default::default(),
+            fixed_widths: Default::default(),
+            width_percentile: Default::default(),
+            overflow: Overflow::Push,
+            truncate_at: TruncateAt::End,
+            truncate_column: Default::default(),
+            truncate_marker: Default::default(),
+            wrap: Default::default(),
+            valign: VAlign::Top,
+            flush_right_last: Default::default(),
+            width: Default::default(),
+            hex: Default::default(),
+            ip: Default::default(),
+            decimal_comma: Default::default(),
+            fmt: Default::default(),
+            round_to: Default::default(),
+            timestamps: Default::default(),
+            infer: Default::default(),
+            number: Default::default(),
+            number_start: 1,
+            number_format: "{}".to_string(),
+            totals: Default::default(),
+            total_stats: vec![Stat::Sum],
+            ruler: Default::default(),
+            template: Default::default(),
+            color_if: Default::default(),
+            lines: Default::default(),
+            head: Default::default(),
+            tail: Default::default(),
+            all_widths: Default::default(),
+            reverse: Default::default(),
+            sort_by: Default::default(),
+            skip: Default::default(),
+            header: Default::default(),
+            header_style: Default::default(),
+            reheader: Default::default(),
+            max_line_bytes: Default::default(),
+            long_lines: LongLines::Error,
+            decompress: Default::default(),
+            null: Default::default(),
+            record_sep: Default::default(),
+            encoding: Default::default(),
+            keep_encoding: Default::default(),
+            keep_bom: Default::default(),
+            tokenizer: Default::default(),
+            measure: Measure::Unicode,
+            width_measure: Default::default(),
+            map_cell: Default::default(),
+            cell_hook: Default::default(),
+        }
+    }
+}
+
+impl AlignConfig {
+    /// The byte terminating input records.
+    fn record_delim(&self) -> u8 {
+        match self.null {
+            true => 0,
+            false => b'\n',
+        }
+    }
+
+    /// Returns the width of `s` according to the configured measure.
+    ///
+    /// Only the last line of `s` counts, as in the cells of rows joined by
+    /// --join-brackets.
+    fn width(&self, s: &str) -> usize {
+        let s = s.rsplit('\n').next().unwrap_or_default();
+        match &self.width_measure {
+            Some(measure) => measure.width(s),
+            None => self.measure.width(s),
+        }
+    }
+
+    /// The column at which --flush-right-last ends the lines.
+    fn right_margin(&self) -> usize {
+        if let Some(width) = self.width {
+            return width;
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some((terminal_size::Width(width), _)) = terminal_size::terminal_size() {
+            return width as usize;
+        }
+        std::env::var("COLUMNS")
+            .ok()
+            .and_then(|c| c.parse().ok())
+            .unwrap_or(80)
+    }
+
+    /// The index of the cell that contains the rest of the line.
+    fn until(&self) -> usize {
+        match self.columns {
+            Some(n) => n.get() - 1,
+            None => self.until,
+        }
+    }
+
+    /// Whether --table redraws the frame of the table.
+    fn draws_frame(&self) -> bool {
+        self.table && !self.to_markdown && self.template.is_none() && self.out_sep == " "
+    }
+
+    /// The text between two columns.
+    fn out_sep(&self) -> &str {
+        match self.psv || self.to_markdown || self.draws_frame() {
+            true => " | ",
+            false => &self.out_sep,
+        }
+    }
+
+    /// The string delimiter of the column `i`, starting at 0.
+    fn str_delim(&self, i: usize) -> Option<char> {
+        if self.string_delim.is_empty() {
+            return Some(self.str_delim);
+        }
+        self.string_delim
+            .iter()
+            .find(|d| d.column.get() == i + 1)
+            .map(|d| d.delim)
+    }
+
+    /// The decimal separator of numbers.
+    fn decimal(&self) -> char {
+        match self.decimal_comma {
+            true => ',',
+            false => '.',
+        }
+    }
+}
+
+/// Returns the offset of the first position outside of strings at which `is_sep` matches.
+fn find_sep(line: &[u8], str_delim: Option<char>, is_sep: impl Fn(&[u8]) -> bool) -> Option<usize> {
+    let mut esc = false;
+    let mut string = false;
+    for (i, &c) in line.iter().enumerate() {
+        if !esc && Some(c as char) == str_delim {
+            string = !string;
+        }
+        esc = !esc && c == b'\\';
+        if !string && is_sep(&line[i..]) {
+            return Some(i);
+        }
+    }
+    None
+}
+
+fn split_whitespace(line: &[u8], opts: &AlignConfig) -> Vec<(usize, usize)> {
+    let is_sep = |s: &[u8]| match opts.two_space {
+        true => s[0] == b'\t' || s.starts_with(b"  "),
+        false => is_indent(s[0]),
+    };
+    let mut words = Vec::new();
+    let mut pos = 0;
+    while pos < line.len() {
+        pos += match line[pos..]
+            .iter()
+            .position(|&c| !(c as char).is_whitespace())
+        {
+            Some(i) => i,
+            None => break,
+        };
+        if words.len() == opts.until() {
+            words.push((pos, line.len()));
+            break;
+        }
+        let start = pos;
+        let end = match find_sep(&line[start..], opts.str_delim(words.len()), is_sep) {
+            Some(i) => start + i,
+            None => line.len(),
+        };
+        pos = end;
+        words.push(trim(line, start, end));
+    }
+    words
+}
+
+fn split_fields(line: &[u8], sep: &[u8], opts: &AlignConfig) -> Vec<(usize, usize)> {
+    let mut words = Vec::new();
+    if line.iter().all(|&c| is_indent(c)) {
+        return words;
+    }
+    let mut pos = 0;
+    loop {
+        let start = pos;
+        let mut end = line.len();
+        if words.len() < opts.until() {
+            // With --psv, a pipe preceded by an odd number of backslashes is escaped.
+            let escaped = |s: &[u8]| {
+                let before = &line[..line.len() - s.len()];
+                before.iter().rev().take_while(|&&c| c == b'\\').count() % 2 == 1
+            };
+            if let Some(i) = find_sep(&line[start..], opts.str_delim(words.len()), |s| {
+                s.starts_with(sep) && !(opts.psv && escaped(s))
+            }) {
+                end = start + i;
+            }
+        }
+        let (first, last) = trim(line, start, end);
+        if first < last || opts.keep_empty_fields || opts.psv {
+            words.push((first, last));
+        }
+        if end == line.len() {
+            break;
+        }
+        pos = end + sep.len();
+    }
+    words
+}
+
+/// Splits lines into cells.
+pub trait Tokenizer {
+    /// Returns the byte ranges of the cells of `line`.
+    fn tokenize(&self, line: &str) -> Vec<(usize, usize)>;
+}
+
+impl fmt::Debug for dyn Tokenizer + Send + Sync {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("Tokenizer")
+    }
+}
+
+/// The built-in tokenizer. Lines are split at whitespace, or at the field separator if
+/// there is one, outside of strings.
+impl Tokenizer for AlignConfig {
+    fn tokenize(&self, line: &str) -> Vec<(usize, usize)> {
+        let line = line.as_bytes();
+        match &self.field_sep {
+            _ if self.psv => split_fields(line, b"|", self),
+            Some(sep) => split_fields(line, sep.as_bytes(), self),
+            None => {
+                let mut words = split_whitespace(line, self);
+                if self.timestamps.is_some() {
+                    timestamp::merge(line, &mut words);
+                }
+                words
+            }
+        }
+    }
+}
+
+/// Splits a line into cells with the configured tokenizer.
+fn split(line: &str, opts: &AlignConfig) -> Vec<(usize, usize)> {
+    match &opts.tokenizer {
+        Some(tokenizer) => tokenizer.tokenize(line),
+        None => opts.tokenize(line),
+    }
+}
+
+/// Removes spaces and tabs from both ends of `line[start..end]`.
+fn trim(line: &[u8], mut start: usize, mut end: usize) -> (usize, usize) {
+    while start < end && is_indent(line[start]) {
+        start += 1;
+    }
+    while start < end && is_indent(line[end - 1]) {
+        end -= 1;
+    }
+    (start, end)
+}
+
+struct Words {
+    line: LineText,
+    words: Vec<(usize, usize)>,
+    /// The additional lines of multi-line cells, indexed by column.
+    more: Vec<Vec<String>>,
+    /// Whether the line is printed as is.
+    verbatim: bool,
+    /// Whether the line keeps its own indentation instead of the common one.
+    own_indent: bool,
+    /// The length of a prefix that is printed as is before the indentation.
+    prefix: usize,
+}
+
+impl Words {
+    fn new(line: LineText, opts: &AlignConfig) -> Words {
+        let bytes = line.as_bytes();
+        let words = if opts.env {
+            match env::split_assignment(bytes) {
+                Some(words) => words,
+                None if bytes.iter().all(|&c| is_indent(c)) => Vec::new(),
+                None => return Words::verbatim(line),
+            }
+        } else if let Some(token) = &opts.on {
+            match anchor::split(bytes, token, opts) {
+                Some(words) => words,
+                None if bytes.iter().all(|&c| is_indent(c)) => Vec::new(),
+                None => return Words::verbatim(line),
+            }
+        } else if opts.from_markdown {
+            match markdown::split_row(bytes) {
+                Some(words) => words,
+                None if bytes.iter().all(|&c| is_indent(c)) => Vec::new(),
+                None => return Words::verbatim(line),
+            }
+        } else if opts.table {
+            match table::split_row(bytes) {
+                Some(words) => words,
+                None if bytes.iter().all(|&c| is_indent(c)) => Vec::new(),
+                None => return Words::verbatim(line),
+            }
+        } else {
+            split(&line, opts)
+        };
+        Words::with_words(line, words)
+    }
+
+    fn with_words(line: impl Into<LineText>, words: Vec<(usize, usize)>) -> Words {
+        Words {
+            line: line.into(),
+            words,
+            more: Vec::new(),
+            verbatim: false,
+            own_indent: false,
+            prefix: 0,
+        }
+    }
+
+    /// Creates a line that is printed as is.
+    fn verbatim(line: impl Into<LineText>) -> Words {
+        Words {
+            verbatim: true,
+            ..Words::with_words(line, Vec::new())
+        }
+    }
+
+    /// Returns the text before the first cell if the line keeps its own indentation.
+    fn own_indent(&self) -> Option<&[u8]> {
+        match self.words.first() {
+            Some(&(start, _)) if self.own_indent => Some(&self.line.as_bytes()[self.prefix..start]),
+            _ => None,
+        }
+    }
+
+    fn is_blank(&self) -> bool {
+        self.words.len() == 0 && !self.verbatim && self.prefix == 0
+    }
+
+    /// The number of output lines of this row.
+    fn height(&self) -> usize {
+        1 + self.more.iter().map(|m| m.len()).max().unwrap_or(0)
+    }
+
+    /// Returns the cells of the `n`th output line of this row.
+    fn row(&self, n: usize, valign: VAlign) -> Vec<&str> {
+        if n == 0 && valign == VAlign::Top {
+            return self.iter().collect();
+        }
+        let height = self.height();
+        let mut words = self.iter();
+        // The cell of each column on this line, if the column has one.
+        let mut cells = Vec::new();
+        for i in 0..self.words.len().max(self.more.len()) {
+            let word = words.next();
+            let more = self.more.get(i).map(|m| &m[..]).unwrap_or_default();
+            let lines = usize::from(word.is_some()) + more.len();
+            let offset = match valign {
+                VAlign::Top => 0,
+                VAlign::Middle => (height - lines) / 2,
+                VAlign::Bottom => height - lines,
+            };
+            cells.push(match (n.checked_sub(offset), word) {
+                (Some(0), Some(word)) => Some(word),
+                (Some(k), Some(_)) => more.get(k - 1).map(|s| &**s),
+                (Some(k), None) => more.get(k).map(|s| &**s),
+                (None, _) => None,
+            });
+        }
+        let len = cells.iter().rposition(|c| c.is_some()).map_or(0, |i| i + 1);
+        cells[..len].iter().map(|c| c.unwrap_or("")).collect()
+    }
+
+    /// Creates a line with the given cells separated by spaces.
+    fn from_cells(cells: &[String]) -> Words {
+        let mut line = String::new();
+        let mut words = Vec::new();
+        for cell in cells {
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            words.push((line.len(), line.len() + cell.len()));
+            line.push_str(cell);
+        }
+        Words::with_words(line, words)
+    }
+
+    /// Moves the lines of cells containing line breaks after the first to the additional
+    /// lines of their columns.
+    fn split_lines(&mut self) {
+        if !self.line.contains('\n') {
+            return;
+        }
+        if self.verbatim || self.words.len() == 0 {
+            // Lines printed as is cannot span multiple lines.
+            self.line = self.line.replace('\n', " ").into();
+            return;
+        }
+        let mut more = Vec::new();
+        self.map(|i, cell| {
+            let mut lines = cell.split('\n');
+            let first = lines.next().unwrap_or_default().to_string();
+            if more.len() <= i {
+                more.resize(i + 1, Vec::new());
+            }
+            more[i] = lines.map(|l| l.to_string()).collect();
+            Some(first)
+        });
+        // Line breaks between the cells are spaces.
+        self.line = self.line.replace('\n', " ").into();
+        self.more = more;
+    }
+
+    /// Merges the cells inside brackets that span the line breaks of a row joined by
+    /// --join-brackets, so that each line break stays inside a single cell.
+    fn join_brackets(&mut self, delim: char) {
+        if !self.line.contains('\n') {
+            return;
+        }
+        let mut words: Vec<(usize, usize)> = Vec::with_capacity(self.words.len());
+        for &(start, end) in &self.words {
+            if let Some(last) = words.last_mut() {
+                let open = bracket_depth(&self.line[..last.1], delim) > 0;
+                if open && self.line[last.0..end].contains('\n') {
+                    last.1 = end;
+                    continue;
+                }
+            }
+            words.push((start, end));
+        }
+        self.words = words;
+    }
+
+    /// Returns the text between each pair of adjacent cells.
+    fn separators(&self) -> Vec<&str> {
+        self.words
+            .windows(2)
+            .map(|w| self.line.get(w[0].1..w[1].0).unwrap_or_default())
+            .collect()
+    }
+
+    /// Replaces the cells for which `f` returns a new text.
+    fn map(&mut self, mut f: impl FnMut(usize, &str) -> Option<String>) {
+        let mut line = String::new();
+        let mut prev = 0;
+        let mut changed = false;
+        for (i, word) in self.words.iter_mut().enumerate() {
+            let (start, end) = *word;
+            line.push_str(&self.line[prev..start]);
+            let text = &self.line[start..end];
+            let start = line.len();
+            match f(i, text) {
+                Some(text) => {
+                    line.push_str(&text);
+                    changed = true;
+                }
+                None => line.push_str(text),
+            }
+            *word = (start, line.len());
+            prev = end;
+        }
+        // Unchanged lines of memory-mapped files stay in the map.
+        if changed {
+            line.push_str(&self.line[prev..]);
+            self.line = line.into();
+        }
+    }
+
+    fn iter(&self) -> WordIter<'_> {
+        WordIter {
+            pos: 0,
+            line: &self.line,
+            words: &self.words,
+        }
+    }
+}
+
+struct WordIter<'a> {
+    pos: usize,
+    line: &'a str,
+    words: &'a Vec<(usize, usize)>,
+}
+
+impl<'a> Iterator for WordIter<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.pos < self.words.len() {
+            let (start, end) = self.words[self.pos];
+            self.pos += 1;
+            Some(&self.line[start..end])
+        } else {
+            None
+        }
+    }
+}
+
+fn is_indent(c: u8) -> bool {
+    c == b' ' || c == b'\t'
+}
+
+struct Line {
+    words: Words,
+    /// The index of the group whose column widths apply to this line.
+    group: usize,
+    /// Whether the line is the header of its input.
+    header: bool,
+    /// Whether the line is a rule, whose cells are drawn once the column widths are known.
+    rule: Option<Rule>,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Rule {
+    /// The rule above the totals.
+    Totals,
+    /// The delimiter row of a Markdown table.
+    Markdown,
+    /// A border of a table redrawn by --table.
+    Border,
+}
+
+struct Input {
+    indent: Option<Vec<u8>>,
+    lines: Vec<Line>,
+    config: EditorConfig,
+    /// The encoding the input was decoded from.
+    encoding: &'static encoding_rs::Encoding,
+    /// Whether the input started with a byte order mark.
+    bom: bool,
+}
+
+/// The decoded lines of an input.
+struct Text {
+    lines: Vec<LineText>,
+    encoding: &'static encoding_rs::Encoding,
+    bom: bool,
+}
+
+/// Reads the next record into `buf`, keeping at most `max` bytes of it.
+///
+/// Returns whether the record was longer than `max` bytes, or `None` at the end of the
+/// input.
+fn read_record(
+    input: &mut impl BufRead,
+    delim: u8,
+    max: usize,
+    buf: &mut Vec<u8>,
+) -> std::io::Result<Option<bool>> {
+    buf.clear();
+    let mut read = false;
+    let mut long = false;
+    loop {
+        let available = input.fill_buf()?;
+        if available.is_empty() {
+            break;
+        }
+        read = true;
+        let (content, used) = match available.iter().position(|&c| c == delim) {
+            Some(i) => (&available[..i], i + 1),
+            None => (available, available.len()),
+        };
+        let room = max - buf.len();
+        long |= content.len() > room;
+        buf.extend_from_slice(&content[..content.len().min(room)]);
+        let done = used > content.len();
+        input.consume(used);
+        if done {
+            break;
+        }
+    }
+    Ok(read.then_some(long))
+}
+
+/// Applies the policy for lines longer than `--max-line-bytes` to line `n` and returns
+/// whether the line is kept.
+fn long_line(opts: &AlignConfig, n: usize, log: &mut Vec<Diagnostic>) -> Result<bool, AlignError> {
+    let max = opts.max_line_bytes.unwrap_or_default();
+    match opts.long_lines {
+        LongLines::Error => Err(AlignError::LineTooLong { line: n, max }),
+        LongLines::Skip => {
+            warning!(log, "skipping line {n}, which is longer than {max} bytes");
+            Ok(false)
+        }
+        LongLines::Truncate => {
+            warning!(log, "truncating line {n}, which is longer than {max} bytes");
+            Ok(true)
+        }
+    }
+}
+
+/// Reads and decodes the lines of the input. Errors name the file at `path`, if any.
+///
+/// Invalid UTF-8 is replaced by U+FFFD.
+fn read_as_unicode(
+    mut input: impl BufRead,
+    path: Option<&Path>,
+    opts: &AlignConfig,
+    log: &mut Vec<Diagnostic>,
+) -> Result<Text, AlignError> {
+    let io = |source| AlignError::Io {
+        path: path.map(Path::to_path_buf),
+        source,
+    };
+    let delim = opts.record_delim();
+    let bom = encoding_rs::Encoding::for_bom(input.fill_buf().map_err(io)?);
+    let encoding = match (opts.encoding, bom) {
+        (Some(encoding), _) => Some(encoding),
+        // Input with a UTF-16 byte order mark cannot be read as UTF-8.
+        (None, Some((encoding, _))) if encoding != encoding_rs::UTF_8 => {
+            Some(InputEncoding::Fixed(encoding))
+        }
+        _ => None,
+    };
+    if let Some(encoding) = encoding {
+        let mut bytes = Vec::new();
+        input.read_to_end(&mut bytes).map_err(io)?;
+        let (text, encoding) = encoding::decode(&bytes, encoding);
+        let mut lines: Vec<LineText> = text
+            .split(delim as char)
+            .map(|l| l.to_string().into())
+            .collect();
+        if lines.last().is_some_and(|l| l.is_empty()) {
+            lines.pop();
+        }
+        if delim == b'\n' {
+            for line in &mut lines {
+                if line.ends_with('\r') {
+                    line.to_mut().pop();
+                }
+            }
+        }
+        if let Some(max) = opts.max_line_bytes {
+            let mut kept = Vec::with_capacity(lines.len());
+            for (n, mut line) in lines.into_iter().enumerate() {
+                if line.len() > max {
+                    if !long_line(opts, n + 1, log)? {
+                        continue;
+                    }
+                    let mut len = max;
+                    while !line.is_char_boundary(len) {
+                        len -= 1;
+                    }
+                    line.to_mut().truncate(len);
+                }
+                kept.push(line);
+            }
+            lines = kept;
+        }
+        return Ok(Text {
+            lines,
+            encoding,
+            bom: bom.is_some(),
+        });
+    }
+    if let Some((_, len)) = bom {
+        input.consume(len);
+    }
+    let mut lines = Vec::new();
+    let max = opts.max_line_bytes.unwrap_or(usize::MAX);
+    let mut line = Vec::new();
+    for n in 1.. {
+        let Some(long) = read_record(&mut input, delim, max, &mut line).map_err(io)? else {
+            break;
+        };
+        if delim == b'\n' && line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        if long {
+            if !long_line(opts, n, log)? {
+                continue;
+            }
+            // The line may have been cut in the middle of a character.
+            if let Err(e) = std::str::from_utf8(&line) {
+                if e.error_len().is_none() {
+                    line.truncate(e.valid_up_to());
+                }
+            }
+        }
+        lines.push(String::from_utf8_lossy(&line).into_owned().into());
+    }
+    Ok(Text {
+        lines,
+        encoding: encoding_rs::UTF_8,
+        bom: bom.is_some(),
+    })
+}
+
+fn parse_lines(
+    opts: &AlignConfig,
+    text: Vec<impl Into<LineText>>,
+    log: &mut Vec<Diagnostic>,
+) -> Result<(Option<Vec<u8>>, Vec<Line>), AlignError> {
+    let text: Vec<LineText> = text.into_iter().map(Into::into).collect();
+    let is_blank = |l: &LineText| l.trim().is_empty();
+    if opts.min_lines > Some(text.iter().filter(|l| !is_blank(l)).count()) {
+        let lines = text.into_iter().map(|line| Line {
+            words: Words::verbatim(line),
+            group: 0,
+            header: false,
+            rule: None,
+        });
+        return Ok((None, lines.collect()));
+    }
+    let text = match &opts.record_sep {
+        Some(sep) => split_records(text, sep),
+        None => text,
+    };
+    let text = match opts.multiline_strings {
+        true => join_strings(text, opts.str_delim),
+        false => text,
+    };
+    let text = match opts.join_brackets {
+        true => join_brackets(text, opts.str_delim),
+        false => text,
+    };
+    let mut indent: Option<Vec<u8>> = None;
+    let mut lines: Vec<Line> = Vec::new();
+    let mut yaml = Yaml::default();
+    let mut ini = Ini::default();
+    let mut sql = Sql::default();
+    let mut diff = Diff::default();
+    let mut heredoc = env::Heredoc::default();
+    let mut header = opts.header;
+    // The number of cells of the first row with --strict.
+    let mut columns = None;
+    let mut indent_check = indent::IndentCheck::default();
+    for (n, line) in text.into_iter().enumerate() {
+        if n < opts.skip || opts.lines.is_some_and(|r| !r.contains(n + 1)) {
+            lines.push(Line {
+                words: Words::verbatim(line),
+                group: 0,
+                header: false,
+                rule: None,
+            });
+            continue;
+        }
+        if let (Some(prefix), Some(last)) = (&opts.continuation, lines.last_mut()) {
+            let content = line.trim_start_matches([' ', '\t']);
+            let col = last.words.words.len().checked_sub(1);
+            if let Some(col) = col.filter(|_| content.starts_with(&**prefix)) {
+                let more = &mut last.words.more;
+                if more.len() <= col {
+                    more.resize(col + 1, Vec::new());
+                }
+                more[col].push(content.to_string());
+                continue;
+            }
+        }
+        if opts.env && heredoc.skip(&line) {
+            lines.push(Line {
+                words: Words::verbatim(line),
+                group: 0,
+                header: false,
+                rule: None,
+            });
+            continue;
+        }
+        if opts.table && table::is_border(&line) {
+            if opts.draws_frame() {
+                lines.push(Line {
+                    words: Words::verbatim(line),
+                    group: 0,
+                    header: false,
+                    rule: Some(Rule::Border),
+                });
+            }
+            continue;
+        }
+        let (words, group) = if opts.yaml {
+            yaml.parse(line)
+        } else if opts.ini {
+            ini.parse(line)
+        } else if opts.sql {
+            sql.parse(line)
+        } else if opts.diff {
+            (diff.parse(line, opts), 0)
+        } else {
+            (Words::new(line, opts), 0)
+        };
+        let mut words = words;
+        if opts.multiline_strings {
+            words.split_lines();
+        }
+        if opts.join_brackets {
+            words.join_brackets(opts.str_delim);
+        }
+        let words = match opts.keep_single && !words.verbatim && words.words.len() == 1 {
+            true => Words::verbatim(words.line),
+            false => words,
+        };
+        if !words.verbatim && !words.is_blank() && !words.own_indent {
+            indent_check.check(n + 1, &words.line.as_bytes()[words.prefix..]);
+        }
+        if indent.is_none() && !words.verbatim && !words.is_blank() {
+            let tmp = words.line.as_bytes()[words.prefix..]
+                .iter()
+                .copied()
+                .take_while(|c| is_indent(*c))
+                .collect();
+            indent = Some(tmp);
+        }
+        if words.is_blank() {
+            let prev_blank = lines.last().is_some_and(|l| l.words.is_blank());
+            match opts.blank {
+                Blank::Keep | Blank::Verbatim => {}
+                Blank::Drop => continue,
+                Blank::Collapse if prev_blank => continue,
+                Blank::Collapse => {}
+            }
+        }
+        let rule =
+            opts.from_markdown && markdown::is_delimiter_row(&words.iter().collect::<Vec<_>>());
+        let rule = rule.then_some(Rule::Markdown);
+        let is_header = header && !words.verbatim && words.words.len() > 0;
+        // Only the first line with cells is a header.
+        header &= !is_header;
+        if opts.strict && !is_header && !words.verbatim && words.words.len() > 0 {
+            let expected = *columns.get_or_insert(words.words.len());
+            if words.words.len() != expected {
+                return Err(AlignError::RaggedRow {
+                    line: n + 1,
+                    expected,
+                    cells: words.iter().map(|c| c.to_string()).collect(),
+                });
+            }
+        }
+        lines.push(Line {
+            words,
+            group,
+            header: is_header,
+            rule,
+        });
+    }
+    let indent = match opts.indent.as_deref() {
+        Some("none") => Some(Vec::new()),
+        Some(explicit) => Some(explicit.as_bytes().to_vec()),
+        None => {
+            indent_check.warn(log);
+            indent
+        }
+    };
+    Ok((indent, lines))
+}
+
+/// Joins the lines into the records separated by `sep`, or by blank lines if `sep` is
+/// empty. The line breaks inside the records are replaced by spaces.
+fn split_records(lines: Vec<LineText>, sep: &str) -> Vec<LineText> {
+    let is_blank = |s: &str| s.chars().all(char::is_whitespace);
+    if sep.is_empty() {
+        let mut records = Vec::new();
+        for paragraph in lines.split(|l| is_blank(l)) {
+            if !paragraph.is_empty() {
+                records.push(paragraph.join(" ").into());
+            }
+        }
+        return records;
+    }
+    lines
+        .join("\n")
+        .split_inclusive(sep)
+        .map(|record| record.trim_start_matches('\n').replace('\n', " "))
+        .filter(|record| !is_blank(record))
+        .map(LineText::from)
+        .collect()
+}
+
+/// Returns the number of brackets opened by the line minus the number of brackets it
+/// closes. Brackets inside strings are ignored.
+fn bracket_depth(line: &str, delim: char) -> isize {
+    let mut depth = 0;
+    let mut esc = false;
+    let mut string = false;
+    for c in line.chars() {
+        if !esc && c == delim {
+            string = !string;
+        }
+        esc = !esc && c == '\\';
+        match c {
+            _ if string => {}
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    depth
+}
+
+/// Joins the lines with unclosed brackets to the following lines up to the one that
+/// closes them.
+fn join_brackets(lines: Vec<LineText>, delim: char) -> Vec<LineText> {
+    let mut res: Vec<LineText> = Vec::new();
+    // The number of brackets left open by the last line.
+    let mut depth = 0;
+    for line in lines {
+        let open = depth > 0;
+        depth = (depth + bracket_depth(&line, delim)).max(0);
+        match res.last_mut().filter(|_| open) {
+            Some(last) => {
+                let last = last.to_mut();
+                last.push('\n');
+                last.push_str(&line);
+            }
+            None => res.push(line),
+        }
+    }
+    res
+}
+
+/// Joins the lines spanned by strings that are still open at the end of a line.
+fn join_strings(lines: Vec<LineText>, delim: char) -> Vec<LineText> {
+    let mut res: Vec<LineText> = Vec::new();
+    let mut open = false;
+    for line in lines {
+        match open {
+            true => {
+                let last = res.last_mut().unwrap().to_mut();
+                last.push('\n');
+                last.push_str(&line);
+            }
+            false => res.push(line.clone()),
+        }
+        let mut esc = false;
+        for c in line.chars() {
+            if !esc && c == delim {
+                open = !open;
+            }
+            esc = !esc && c == '\\';
+        }
+    }
+    res
+}
+
+/// Assigns the lines to groups, computes the column widths of each group, and removes the
+/// lines that are not printed.
+fn compute_groups(opts: &AlignConfig, inputs: &mut [Input]) -> Vec<Positioning> {
+    if !opts.all_widths {
+        limit_lines(opts, inputs);
+    }
+    let groups = group_lines(opts, inputs);
+    if opts.all_widths {
+        limit_lines(opts, inputs);
+    }
+    if opts.reverse {
+        for input in inputs {
+            let start = header_lines(input).last().map_or(0, |&h| h + 1);
+            input.lines[start..].reverse();
+        }
+    }
+    groups
+}
+
+/// Removes the lines that are not selected by --head and --tail.
+fn limit_lines(opts: &AlignConfig, inputs: &mut [Input]) {
+    if opts.head.is_none() && opts.tail.is_none() {
+        return;
+    }
+    for input in inputs {
+        let header = header_lines(input);
+        let rows = input.lines.len() - header.len();
+        let mut row = 0;
+        let mut keep = Vec::with_capacity(input.lines.len());
+        for i in 0..input.lines.len() {
+            if header.contains(&i) {
+                keep.push(true);
+                continue;
+            }
+            let head = opts.head.is_some_and(|n| row < n);
+            let tail = opts.tail.is_some_and(|n| rows - row <= n);
+            keep.push(head || tail);
+            row += 1;
+        }
+        let mut keep = keep.into_iter();
+        input.lines.retain(|_| keep.next().unwrap());
+    }
+}
+
+/// Assigns the lines to groups and computes the column widths of each group.
+fn group_lines(opts: &AlignConfig, inputs: &mut [Input]) -> Vec<Positioning> {
+    let mut groups = vec![opts.positioning.clone()];
+    if opts.yaml || opts.ini || opts.sql {
+        // The lines have already been assigned to the mappings, sections, or statements of
+        // their input.
+        for input in inputs.iter_mut() {
+            let base = groups.len();
+            for line in &mut input.lines {
+                line.group += base;
+            }
+            let len = input
+                .lines
+                .iter()
+                .map(|l| l.group + 1)
+                .max()
+                .unwrap_or(base);
+            groups.resize(len, opts.positioning.clone());
+        }
+    } else {
+        let per_file = opts.widths == WidthScope::PerFile;
+        let mut key: Option<String> = None;
+        // Whether a blank line ends the current block.
+        let mut blank = false;
+        // The number of columns and the indentation of the last line.
+        let mut shape: Option<(usize, String)> = None;
+        for (n, input) in inputs.iter_mut().enumerate() {
+            if per_file && n > 0 {
+                groups.push(opts.positioning.clone());
+                key = None;
+                blank = false;
+                shape = None;
+            }
+            for line in &mut input.lines {
+                if line.words.words.len() > 0 {
+                    let k = match opts.group_by {
+                        Some(col) => line.words.iter().nth(col.get() - 1).unwrap_or(""),
+                        None => "",
+                    };
+                    let mut new_block = blank;
+                    if opts.auto_blocks {
+                        let words = &line.words;
+                        let indent = &words.line[words.prefix..words.words[0].0];
+                        let next = (words.words.len(), indent.to_string());
+                        new_block |= shape.as_ref().is_some_and(|shape| *shape != next);
+                        shape = Some(next);
+                    }
+                    let new_block = new_block && key.is_some();
+                    if new_block || key.as_deref().is_some_and(|key| key != k) {
+                        groups.push(opts.positioning.clone());
+                    }
+                    key = Some(k.to_string());
+                    blank = false;
+                } else if (opts.blocks || opts.auto_blocks) && line.words.is_blank() {
+                    blank = true;
+                }
+                line.group = groups.len() - 1;
+                // Blocks can be indented differently.
+                line.words.own_indent |= opts.auto_blocks || opts.indent_column || opts.by_indent;
+            }
+        }
+        if opts.by_indent {
+            // Split each group by the indentation of its lines.
+            let mut levels = HashMap::new();
+            for line in inputs.iter_mut().flat_map(|i| &mut i.lines) {
+                let words = &line.words;
+                let indent = words
+                    .words
+                    .first()
+                    .map(|&(start, _)| words.line[words.prefix..start].to_string());
+                let len = levels.len();
+                line.group = *levels.entry((line.group, indent)).or_insert(len);
+            }
+            groups = vec![opts.positioning.clone(); levels.len().max(1)];
+        }
+    }
+    if opts.infer {
+        infer::apply(opts, inputs, &mut groups);
+    }
+    if opts.from_markdown || opts.to_markdown {
+        markdown::prepare(opts, inputs, &mut groups);
+    }
+    match &opts.empty {
+        Some(placeholder) => fill_empty(inputs, groups.len(), placeholder),
+        // The closing pipes of short rows line up with those of the other rows.
+        None if opts.to_markdown || opts.draws_frame() => fill_empty(inputs, groups.len(), ""),
+        None => {}
+    }
+    for input in inputs.iter() {
+        for line in &input.lines {
+            add_widths(opts, input, line, &mut groups[line.group]);
+        }
+    }
+    if let Some(percent) = opts.width_percentile {
+        percentile_widths(opts, inputs, &mut groups, percent);
+    }
+    for positioning in &mut groups {
+        let sep = opts.width(opts.out_sep());
+        for (i, stop) in opts.stops.windows(2).enumerate() {
+            positioning
+                .max_width
+                .set(i, (stop[1] - stop[0]).saturating_sub(sep));
+        }
+        for (i, &width) in opts.fixed_widths.iter().enumerate() {
+            positioning.max_width.set(i, width);
+        }
+    }
+    totals::draw_rules(inputs, |line, i| groups[line.group].max_width.get(i));
+    if opts.to_markdown {
+        markdown::draw_delimiters(inputs, &groups);
+    }
+    if opts.draws_frame() {
+        table::draw_borders(inputs, &groups);
+    }
+    groups
+}
+
+/// Replaces empty cells by the placeholder and adds placeholders to lines with fewer cells
+/// than the other lines of their group.
+fn fill_empty(inputs: &mut [Input], groups: usize, placeholder: &str) {
+    let mut columns = vec![0; groups];
+    for line in inputs.iter().flat_map(|i| &i.lines) {
+        columns[line.group] = columns[line.group].max(line.words.words.len());
+    }
+    for line in inputs.iter_mut().flat_map(|i| &mut i.lines) {
+        let words = &mut line.words;
+        if words.words.len() == 0 {
+            continue;
+        }
+        words.map(|_, cell| cell.is_empty().then(|| placeholder.to_string()));
+        while words.words.len() < columns[line.group] {
+            let line = words.line.to_mut();
+            line.push(' ');
+            let start = line.len();
+            line.push_str(placeholder);
+            words.words.push((start, line.len()));
+        }
+    }
+}
+
+/// Widens the columns of `positioning` to fit the cells of the line.
+fn add_widths(opts: &AlignConfig, input: &Input, line: &Line, positioning: &mut Positioning) {
+    let indent = indent_width(opts, input, &line.words);
+    for n in 0..line.words.height() {
+        for (i, word) in line.words.row(n, opts.valign).into_iter().enumerate() {
+            let mut width = opts.width(word);
+            let align = positioning.align.get(i);
+            if matches!(align, Decimal | PathAlign) && !line.header {
+                let (int, frac) = split_anchored(opts, align, word);
+                let int = opts.width(int).max(positioning.int_width.get(i));
+                let frac = opts.width(frac).max(positioning.frac_width.get(i));
+                positioning.int_width.set(i, int);
+                positioning.frac_width.set(i, frac);
+                width = int + frac;
+            }
+            if i == 0 {
+                width += indent;
+            }
+            let width = width.min(positioning.limit.get(i));
+            if width > positioning.max_width.get(i) {
+                positioning.max_width.set(i, width);
+            }
+        }
+    }
+}
+
+/// Shrinks the columns to the given percentile of the widths of their cells.
+fn percentile_widths(
+    opts: &AlignConfig,
+    inputs: &[Input],
+    groups: &mut [Positioning],
+    percent: u8,
+) {
+    let mut widths: Vec<Vec<Vec<usize>>> = vec![Vec::new(); groups.len()];
+    for input in inputs {
+        for line in &input.lines {
+            let columns = &mut widths[line.group];
+            let indent = indent_width(opts, input, &line.words);
+            for n in 0..line.words.height() {
+                for (i, word) in line.words.row(n, opts.valign).into_iter().enumerate() {
+                    if columns.len() <= i {
+                        columns.resize(i + 1, Vec::new());
+                    }
+                    let indent = if i == 0 { indent } else { 0 };
+                    columns[i].push(opts.width(word) + indent);
+                }
+            }
+        }
+    }
+    for (positioning, columns) in groups.iter_mut().zip(widths) {
+        for (i, mut column) in columns.into_iter().enumerate() {
+            column.sort_unstable();
+            // The nearest rank.
+            let rank = (column.len() * usize::from(percent)).div_ceil(100);
+            let width = column[rank.max(1) - 1].max(opts.positioning.max_width.get(i));
+            if width < positioning.max_width.get(i) {
+                positioning.max_width.set(i, width);
+            }
+        }
+    }
+}
+
+/// The width of the indentation that counts toward the first column.
+fn indent_width(opts: &AlignConfig, input: &Input, words: &Words) -> usize {
+    match words.own_indent() {
+        Some(indent) if opts.indent_column => line_width(opts, &input.config.reindent(indent)),
+        _ => 0,
+    }
+}
+
+/// Returns the position of the decimal separator, or the length, of `s` if it is a number
+/// such as `-1,234.5`.
+///
+/// If the decimal separator is `.` or `,`, the other one is accepted as a thousands
+/// separator in the integer part.
+fn parse_number(s: &str, decimal: char) -> Option<usize> {
+    let thousands = match decimal {
+        '.' => ',',
+        ',' => '.',
+        _ => decimal,
+    };
+    let sign = s.len() - s.trim_start_matches(['+', '-']).len();
+    if sign > 1 {
+        return None;
+    }
+    let sep = s.find(decimal).unwrap_or(s.len());
+    let (int, frac) = (
+        &s[sign..sep],
+        s[sep..].get(decimal.len_utf8()..).unwrap_or(""),
+    );
+    let valid = int.len() + frac.len() > 0
+        && !int.starts_with(thousands)
+        && int.chars().all(|c| c.is_ascii_digit() || c == thousands)
+        && frac.bytes().all(|c| c.is_ascii_digit());
+    valid.then_some(sep)
+}
+
+fn is_number(s: &str, decimal: char) -> bool {
+    parse_number(s, decimal).is_some()
+}
+
+/// Returns the value of a number such as `-1,234.5`.
+fn number_value(s: &str, decimal: char) -> Option<f64> {
+    parse_number(s, decimal)?;
+    let number: String = s
+        .chars()
+        .filter(|&c| c.is_ascii_digit() || c == '-' || c == decimal)
+        .map(|c| if c == decimal { '.' } else { c })
+        .collect();
+    number.parse().ok()
+}
+
+/// Splits a number into the integer part and the fractional part including the decimal
+/// separator. Other text is treated as an integer part.
+fn split_decimal(s: &str, decimal: char) -> (&str, &str) {
+    s.split_at(parse_number(s, decimal).unwrap_or(s.len()))
+}
+
+/// Splits a cell of a decimally aligned or path column at the point that lines up.
+///
+/// Paths are split after their last `/`.
+fn split_anchored<'a>(opts: &AlignConfig, align: Alignment, s: &'a str) -> (&'a str, &'a str) {
+    match align {
+        PathAlign => s.split_at(s.rfind('/').map_or(0, |i| i + 1)),
+        _ => split_decimal(s, opts.decimal()),
+    }
+}
+
+/// Returns the longest prefix of `s` that is at most `width` columns wide.
+fn prefix<'a>(opts: &AlignConfig, s: &'a str, width: usize) -> &'a str {
+    let mut w = 0;
+    for (i, c) in s.char_indices() {
+        w += opts.width(&s[i..i + c.len_utf8()]);
+        if w > width {
+            return &s[..i];
+        }
+    }
+    s
+}
+
+/// Returns the longest suffix of `s` that is at most `width` columns wide.
+fn suffix<'a>(opts: &AlignConfig, s: &'a str, width: usize) -> &'a str {
+    let mut w = 0;
+    for (i, c) in s.char_indices().rev() {
+        w += opts.width(&s[i..i + c.len_utf8()]);
+        if w > width {
+            return &s[i + c.len_utf8()..];
+        }
+    }
+    s
+}
+
+/// Shortens `s`, a cell of column `column` starting at 0, to at most `width` columns.
+fn truncate<'a>(opts: &AlignConfig, column: usize, s: &'a str, width: usize) -> Cow<'a, str> {
+    let marker = &opts.truncate_marker;
+    let Some(rest) = width.checked_sub(opts.width(marker)) else {
+        return Cow::Borrowed(prefix(opts, s, width));
+    };
+    let at = opts
+        .truncate_column
+        .iter()
+        .rev()
+        .find(|t| t.column.get() == column + 1);
+    match at.map_or(opts.truncate_at, |t| t.at) {
+        TruncateAt::End => Cow::Owned(format!("{}{marker}", prefix(opts, s, rest))),
+        TruncateAt::Start => Cow::Owned(format!("{marker}{}", suffix(opts, s, rest))),
+        TruncateAt::Middle => {
+            let start = prefix(opts, s, rest - rest / 2);
+            let end = suffix(opts, s, rest / 2);
+            Cow::Owned(format!("{start}{marker}{end}"))
+        }
+    }
+}
+
+/// An output line.
+struct Row<'a> {
+    indent: &'a [u8],
+    cells: Vec<&'a str>,
+    /// The separators after the cells. Missing separators are the output separator.
+    separators: &'a [&'a str],
+    header: bool,
+    /// The number of columns of the group of the line.
+    columns: usize,
+}
+
+fn write_line(
+    opts: &AlignConfig,
+    positioning: &Positioning,
+    padding: &[u8],
+    row: &Row<'_>,
+    right_margin: Option<usize>,
+    out: &mut Vec<u8>,
+) {
+    let Row {
+        indent,
+        ref cells,
+        separators,
+        header,
+        columns,
+    } = *row;
+    let bold = header && opts.header_style.contains(&HeaderStyle::Bold);
+    let rules = match header {
+        true => &[][..],
+        false => &opts.color_if[..],
+    };
+    let row_color = color::row_color(rules, cells, opts.decimal());
+    let write_cell = |out: &mut Vec<u8>, i: usize, word: &str| {
+        let style = match bold {
+            true => Some(ansi::BOLD),
+            false => color::cell_color(rules, i, cells, opts.decimal()),
+        };
+        let Some(style) = style else {
+            out.extend_from_slice(word.as_bytes());
+            return;
+        };
+        out.extend_from_slice(style.as_bytes());
+        out.extend_from_slice(word.as_bytes());
+        out.extend_from_slice(ansi::RESET.as_bytes());
+        if let Some(row_color) = row_color {
+            out.extend_from_slice(row_color.as_bytes());
+        }
+    };
+    // Whether the last cell is padded and followed by a closing pipe.
+    let closed = (opts.to_markdown || opts.draws_frame()) && cells.len() > 0;
+    if cells.len() > 0 {
+        out.extend_from_slice(indent);
+        if let Some(row_color) = row_color {
+            out.extend_from_slice(row_color.as_bytes());
+        }
+        if let Some(&lead) = opts.stops.first() {
+            out.extend_from_slice(&padding[0..lead]);
+        }
+        if closed {
+            out.extend_from_slice(b"| ");
+        }
+    }
+    // The padding of the justified cells, which is spread across the gaps of the line.
+    // Justified columns that the line lacks count as padding too, so that all lines end
+    // at the same column. Header cells are centered instead.
+    let justified = |i: usize| !header && positioning.align.get(i) == Justified;
+    let missing = (cells.len()..columns)
+        .filter(|&i| justified(i))
+        .map(|i| positioning.max_width.get(i) + opts.width(opts.out_sep()));
+    let slack: usize = cells
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| justified(i))
+        .map(|(i, word)| {
+            positioning
+                .max_width
+                .get(i)
+                .saturating_sub(opts.width(word))
+        })
+        .chain(missing)
+        .sum();
+    let gaps = cells.len().saturating_sub(1);
+    // The number of display columns by which the previous cells overflowed their columns.
+    let mut debt = 0;
+    let mut words = cells.iter().enumerate().peekable();
+    while let Some((i, &word)) = words.next() {
+        let mut max_width = positioning.max_width.get(i);
+        if i == 0 && opts.indent_column {
+            max_width = max_width.saturating_sub(line_width(opts, indent));
+        }
+        if let Some(margin) = right_margin.filter(|_| i > 0 && i + 1 == cells.len()) {
+            let used = line_width(opts, out) + opts.width(word);
+            out.resize(out.len() + margin.saturating_sub(used), b' ');
+            write_cell(out, i, word);
+            break;
+        }
+        let mut word = Cow::Borrowed(word);
+        if opts.width(&word) > max_width {
+            match opts.overflow {
+                Overflow::Push if positioning.limit.get(i) == usize::MAX => {
+                    debt += opts.width(&word) - max_width
+                }
+                _ => word = truncate(opts, i, cells[i], max_width),
+            }
+        }
+        let word = &*word;
+        let mut pad = max_width.saturating_sub(opts.width(word));
+        let paid = debt.min(pad);
+        pad -= paid;
+        debt -= paid;
+        let align = match header {
+            true => Centered,
+            false => positioning.align.get(i),
+        };
+        match align {
+            Left => {
+                write_cell(out, i, word);
+                if words.peek().is_some() || closed {
+                    out.extend_from_slice(&padding[0..pad]);
+                }
+            }
+            Right if positioning.zero.get(i) && is_number(word, opts.decimal()) => {
+                let digits = word.trim_start_matches(['+', '-']);
+                let sign = &word[..word.len() - digits.len()];
+                write_cell(out, i, &format!("{sign}{}{digits}", "0".repeat(pad)));
+            }
+            Right => {
+                out.extend_from_slice(&padding[0..pad]);
+                write_cell(out, i, word);
+            }
+            Decimal | PathAlign => {
+                let (_, frac) = split_anchored(opts, align, word);
+                let right = pad.min(
+                    positioning
+                        .frac_width
+                        .get(i)
+                        .saturating_sub(opts.width(frac)),
+                );
+                out.extend_from_slice(&padding[0..pad - right]);
+                write_cell(out, i, word);
+                if words.peek().is_some() || closed {
+                    out.extend_from_slice(&padding[0..right]);
+                }
+            }
+            Centered => {
+                out.extend_from_slice(&padding[0..pad / 2]);
+                write_cell(out, i, word);
+                if words.peek().is_some() || closed {
+                    out.extend_from_slice(&padding[0..pad - pad / 2]);
+                }
+            }
+            Justified => write_cell(out, i, word),
+        }
+        // With --on, the empty columns between consecutive tokens take up no space.
+        let empty = opts.on.is_some() && max_width == 0 && word.is_empty();
+        if words.peek().is_some() && !empty {
+            let extra = slack / gaps + usize::from(i < slack % gaps);
+            out.resize(out.len() + extra, b' ');
+            let sep = separators.get(i).copied().unwrap_or(opts.out_sep());
+            out.extend_from_slice(sep.as_bytes());
+        }
+    }
+    // Without gaps, the padding follows the only cell.
+    if gaps == 0 && cells.len() > 0 {
+        out.resize(out.len() + slack, b' ');
+    }
+    if closed {
+        out.extend_from_slice(b" |");
+    }
+    if row_color.is_some() {
+        out.extend_from_slice(ansi::RESET.as_bytes());
+    }
+}
+
+/// Returns the display width of a partially written line.
+///
+/// Tabs advance to the next multiple of 8.
+fn line_width(opts: &AlignConfig, line: &[u8]) -> usize {
+    let line = String::from_utf8_lossy(line);
+    let line = ansi::strip(&line);
+    let mut width = 0;
+    for (i, part) in line.split('\t').enumerate() {
+        if i > 0 {
+            width += 8 - width % 8;
+        }
+        width += opts.width(part);
+    }
+    width
+}
+
+/// Collapses runs of whitespace outside of strings to a single space.
+///
+/// Returns `None` if there is nothing to collapse.
+fn squeeze(cell: &str, str_delim: Option<char>) -> Option<String> {
+    let mut res = String::with_capacity(cell.len());
+    let mut esc = false;
+    let mut string = false;
+    let mut space = false;
+    for c in cell.chars() {
+        if !esc && Some(c) == str_delim {
+            string = !string;
+        }
+        esc = !esc && c == '\\';
+        if !string && c.is_whitespace() {
+            space = true;
+            continue;
+        }
+        if space {
+            res.push(' ');
+            space = false;
+        }
+        res.push(c);
+    }
+    (res != cell).then_some(res)
+}
+
+/// Removes the delimiters around a quoted cell and unescapes the delimiters inside.
+fn unquote(cell: &str, delim: char) -> Option<String> {
+    let inner = cell.strip_prefix(delim)?.strip_suffix(delim)?;
+    let escaped = format!("\\{delim}");
+    Some(inner.replace(&escaped, &delim.to_string()))
+}
+
+/// Quotes a cell that contains whitespace and is not quoted yet.
+fn requote(cell: &str, delim: char) -> Option<String> {
+    let quoted = cell.len() > 1 && cell.starts_with(delim) && cell.ends_with(delim);
+    if quoted || !cell.contains(char::is_whitespace) {
+        return None;
+    }
+    let escaped = cell.replace(delim, &format!("\\{delim}"));
+    Some(format!("{delim}{escaped}{delim}"))
+}
+
+/// Applies the cell transformations to the input.
+fn transform(opts: &mut AlignConfig, inputs: &mut [Input]) -> Result<(), AlignError> {
+    if opts.squeeze {
+        for line in inputs.iter_mut().flat_map(|i| &mut i.lines) {
+            line.words.map(|i, cell| squeeze(cell, opts.str_delim(i)));
+        }
+    }
+    if opts.unquote {
+        for line in inputs.iter_mut().flat_map(|i| &mut i.lines) {
+            line.words.map(|i, cell| unquote(cell, opts.str_delim(i)?));
+        }
+    }
+    if opts.requote {
+        for line in inputs.iter_mut().flat_map(|i| &mut i.lines) {
+            line.words
+                .map(|i, cell| requote(cell, opts.str_delim(i).unwrap_or(opts.str_delim)));
+        }
+    }
+    if opts.header_style.contains(&HeaderStyle::Upper) {
+        for line in inputs.iter_mut().flat_map(|i| &mut i.lines) {
+            if line.header {
+                line.words.map(|_, cell| Some(cell.to_uppercase()));
+            }
+        }
+    }
+    if let Some(hook) = &opts.cell_hook {
+        for line in inputs.iter_mut().flat_map(|i| &mut i.lines) {
+            line.words.map(|i, cell| hook(i, cell));
+        }
+    }
+    for command in &opts.map_cell {
+        hook::run(command, inputs)?;
+    }
+    for &rounding in &opts.round_to {
+        round::round(opts, rounding, inputs);
+    }
+    for format in &opts.fmt {
+        printf::format(opts, format, inputs);
+    }
+    if opts.sort_by.len() > 0 {
+        sort::sort(opts, inputs);
+    }
+    totals::append(opts, inputs);
+    if let Some(case) = opts.hex {
+        hex::normalize(&mut opts.positioning, inputs, case);
+    }
+    if opts.ip {
+        ip::normalize(&mut opts.positioning, inputs);
+    }
+    if opts.timestamps == Some(TimestampFormat::Iso) {
+        timestamp::reformat(inputs);
+    }
+    for &column in &opts.wrap {
+        wrap::wrap(opts, column, inputs);
+    }
+    Ok(())
+}
+
+fn write_inputs(
+    opts: &AlignConfig,
+    inputs: &[Input],
+    groups: &[Positioning],
+    out: &mut impl Write,
+) -> Result<(), AlignError> {
+    let padding = {
+        let max_max_width = groups
+            .iter()
+            .flat_map(|p| p.max_width.vec.iter().copied())
+            .chain(opts.stops.first().copied())
+            .max()
+            .unwrap_or(0);
+        let mut padding = Vec::new();
+        padding
+            .try_reserve_exact(max_max_width)
+            .map_err(|_| AlignError::WidthOverflow)?;
+        padding.resize(max_max_width, b' ');
+        padding
+    };
+    let sep = opts.width(opts.out_sep());
+    for positioning in groups {
+        positioning
+            .max_width
+            .vec
+            .iter()
+            .try_fold(0usize, |total, &width| {
+                total.checked_add(width)?.checked_add(sep)
+            })
+            .ok_or(AlignError::WidthOverflow)?;
+    }
+
+    let right_margin = opts.flush_right_last.then(|| opts.right_margin());
+    let format_number = |n: usize| opts.number_format.replace("{}", &n.to_string());
+    let number_width = opts.number.then(|| {
+        let lines = inputs.iter().flat_map(|i| {
+            let header = header_lines(i);
+            i.lines
+                .iter()
+                .enumerate()
+                .filter(move |(n, l)| !header.contains(n) && is_numbered(l))
+        });
+        let numbers = opts.number_start..opts.number_start + lines.count();
+        numbers
+            .map(|n| opts.width(&format_number(n)))
+            .max()
+            .unwrap_or(0)
+    });
+    let mut number = opts.number_start;
+    let mut buf = Vec::new();
+    // The number of columns of each group and whether its ruler has been printed.
+    let mut columns = vec![0; groups.len()];
+    let mut ruled = vec![!opts.ruler; groups.len()];
+    for line in inputs.iter().flat_map(|i| &i.lines) {
+        columns[line.group] = columns[line.group].max(line.words.words.len());
+    }
+    for input in inputs {
+        let indent = input
+            .config
+            .reindent(input.indent.as_deref().unwrap_or_default());
+        let mut write = |buf: &[u8]| match opts.keep_encoding {
+            true => out.write_all(&encoding::encode(buf, input.encoding)),
+            false => out.write_all(buf),
+        };
+        if opts.keep_bom && input.bom {
+            write("\u{feff}".as_bytes())?;
+        }
+        // Inputs without numbered lines, such as those printed as is by --min-lines, are
+        // not indented.
+        let number_width = number_width.filter(|_| input.lines.iter().any(is_numbered));
+        // Writes the line number or, if `numbered` is false, the same amount of space.
+        let mut write_number = |buf: &mut Vec<u8>, numbered: bool| {
+            let Some(width) = number_width else {
+                return;
+            };
+            let text = match numbered {
+                true => {
+                    number += 1;
+                    format_number(number - 1)
+                }
+                false => String::new(),
+            };
+            buf.resize(buf.len() + width.saturating_sub(opts.width(&text)), b' ');
+            buf.extend_from_slice(text.as_bytes());
+            buf.extend_from_slice(opts.out_sep().as_bytes());
+        };
+        let mut write_row = |line: &Line, numbered: bool| -> Result<(), AlignError> {
+            let blank = matches!(opts.blank, Blank::Verbatim) && line.words.is_blank();
+            if line.words.verbatim || blank {
+                buf.clear();
+                if !blank && !line.words.line.is_empty() {
+                    write_number(&mut buf, false);
+                }
+                buf.extend_from_slice(line.words.line.as_bytes());
+                input.config.end_line(&mut buf);
+                write(&buf)?;
+            } else if let Some(template) = opts.template.as_ref().filter(|_| !line.words.is_blank())
+            {
+                buf.clear();
+                write_number(&mut buf, numbered);
+                let cells: Vec<_> = line.words.iter().collect();
+                let text = template.render(opts, &groups[line.group], &cells);
+                buf.extend_from_slice(text.as_bytes());
+                input.config.end_line(&mut buf);
+                write(&buf)?;
+            } else {
+                let positioning = &groups[line.group];
+                let own_indent = line.words.own_indent().map(|i| input.config.reindent(i));
+                let indent = own_indent.as_deref().unwrap_or(&indent);
+                let separators = match opts.keep_sep {
+                    true => line.words.separators(),
+                    false => Vec::new(),
+                };
+                for n in 0..line.words.height() {
+                    buf.clear();
+                    if !line.words.is_blank() {
+                        write_number(&mut buf, numbered && n == 0);
+                    }
+                    buf.extend_from_slice(&line.words.line.as_bytes()[..line.words.prefix]);
+                    let row = Row {
+                        indent,
+                        cells: line.words.row(n, opts.valign),
+                        separators: &separators,
+                        header: line.header,
+                        columns: columns[line.group],
+                    };
+                    write_line(opts, positioning, &padding, &row, right_margin, &mut buf);
+                    input.config.end_line(&mut buf);
+                    write(&buf)?;
+                }
+            }
+            Ok(())
+        };
+        let header = header_lines(input);
+        // The number of rows since the header was last printed.
+        let mut rows = 0;
+        for (i, line) in input.lines.iter().enumerate() {
+            let below_header = header.first().is_some_and(|&h| h < i) && !header.contains(&i);
+            let data = below_header && line.words.words.len() > 0;
+            if let Some(n) = opts.reheader.filter(|_| data) {
+                if rows == n.get() {
+                    for &h in &header {
+                        write_row(&input.lines[h], false)?;
+                    }
+                    rows = 0;
+                }
+                rows += 1;
+            }
+            if line.words.words.len() > 0 && !ruled[line.group] {
+                ruled[line.group] = true;
+                let ruler = ruler(opts, line, &groups[line.group], columns[line.group]);
+                write_row(&ruler, false)?;
+            }
+            write_row(line, !header.contains(&i) && is_numbered(line))?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns a ruler for the columns of the group of `line`, indented like `line`.
+fn ruler(opts: &AlignConfig, line: &Line, positioning: &Positioning, columns: usize) -> Line {
+    let cells: Vec<String> = (0..columns)
+        .map(|i| {
+            let mut width = positioning.max_width.get(i);
+            if i == 0 && opts.indent_column {
+                width = width
+                    .saturating_sub(line.words.own_indent().map_or(0, |i| line_width(opts, i)));
+            }
+            let label = (i + 1).to_string();
+            let mut cell = label.clone();
+            for pos in label.len()..width {
+                cell.push(if pos % 5 == 0 { ':' } else { '-' });
+            }
+            cell
+        })
+        .collect();
+    let mut words = Words::from_cells(&cells);
+    // Keep the indentation of the line and replace its prefix by spaces.
+    let words_start = line.words.words[0].0;
+    let lead = " ".repeat(line.words.prefix) + &line.words.line[line.words.prefix..words_start];
+    words.line.to_mut().insert_str(0, &lead);
+    for word in &mut words.words {
+        word.0 += lead.len();
+        word.1 += lead.len();
+    }
+    words.prefix = line.words.prefix;
+    words.own_indent = line.words.own_indent;
+    Line {
+        words,
+        group: line.group,
+        header: false,
+        rule: None,
+    }
+}
+
+/// Whether the line gets a number with `--number`.
+fn is_numbered(line: &Line) -> bool {
+    !line.words.verbatim && line.words.words.len() > 0
+}
+
+/// Returns the indices of the header of the input and of the rule below it, if any.
+fn header_lines(input: &Input) -> Vec<usize> {
+    let Some(header) = input.lines.iter().position(|l| l.header) else {
+        return Vec::new();
+    };
+    let is_rule = |line: &Line| {
+        let words = &line.words;
+        !words.verbatim
+            && words.words.len() > 0
+            && words.iter().all(|w| w.chars().all(|c| "-=+|:".contains(c)))
+    };
+    match input.lines.get(header + 1) {
+        Some(line) if is_rule(line) => vec![header, header + 1],
+        _ => vec![header],
+    }
+}
+
+/// Aligns the columns of `text`.
+///
+/// Diagnostics are dropped. Use [`align_stream`] to receive them.
+pub fn align(config: &AlignConfig, text: &str) -> Result<String, AlignError> {
+    let mut config = config.clone();
+    // The text has already been decoded.
+    config.encoding = None;
+    let mut out = Vec::new();
+    align_stream(&config, text.as_bytes(), &mut out)?;
+    Ok(String::from_utf8(out).expect("the output of UTF-8 input is UTF-8"))
+}
+
+/// Aligns the columns of the lines read from `input` and writes them to `output`.
+///
+/// All lines are read before the first line is written. Returns the warnings about the
+/// input and the messages describing what was done.
+pub fn align_stream(
+    config: &AlignConfig,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> Result<Vec<Diagnostic>, AlignError> {
+    let mut log = Vec::new();
+    let text = read_as_unicode(input, None, config, &mut log)?;
+    align_text(config, text, &mut output, &mut log)?;
+    Ok(log)
+}
+
+/// Aligns the columns of `lines` and returns the aligned lines without line terminators.
+///
+/// All lines are consumed before the first aligned line is returned. Diagnostics are
+/// dropped.
+pub fn align_lines(
+    lines: impl IntoIterator<Item = String>,
+    config: &AlignConfig,
+) -> Result<impl Iterator<Item = String>, AlignError> {
+    let text = Text {
+        lines: lines.into_iter().map(LineText::from).collect(),
+        encoding: encoding_rs::UTF_8,
+        bom: false,
+    };
+    let mut out = Vec::new();
+    align_text(config, text, &mut out, &mut Vec::new())?;
+    let out = String::from_utf8(out).expect("the output of UTF-8 input is UTF-8");
+    let eol = config.record_delim() as char;
+    let lines: Vec<_> = out.split_terminator(eol).map(|l| l.to_string()).collect();
+    Ok(lines.into_iter())
+}
+
+/// Recovers the cells of aligned text.
+///
+/// Cells are separated by runs of two or more spaces or by tabs, outside of `"`-delimited
+/// strings. For every cell to be recovered, the text must have been aligned with an
+/// output separator of at least two spaces, e.g. with `-o "  "`.
+pub fn parse_aligned(text: &str) -> Vec<Vec<String>> {
+    let opts = AlignConfig {
+        two_space: true,
+        ..AlignConfig::default()
+    };
+    text.lines()
+        .map(|line| {
+            let words = split_whitespace(line.as_bytes(), &opts);
+            words
+                .into_iter()
+                .map(|(start, end)| line[start..end].to_string())
+                .collect()
+        })
+        .collect()
+}
+
+/// Aligns the columns of decoded text.
+fn align_text(
+    config: &AlignConfig,
+    text: Text,
+    out: &mut impl Write,
+    log: &mut Vec<Diagnostic>,
+) -> Result<(), AlignError> {
+    let mut opts = config.clone();
+    if opts.stops.windows(2).any(|w| w[0] >= w[1]) {
+        return Err(AlignError::Stops);
+    }
+    if opts.detect {
+        detect::detect_separator(&mut opts, text.lines.iter().map(|l| &**l), log);
+    }
+    let (indent, lines) = parse_lines(&opts, text.lines, log)?;
+    let mut config = EditorConfig::default();
+    if opts.null {
+        config.set_eol(b"\0");
+    }
+    let mut inputs = [Input {
+        indent,
+        lines,
+        config,
+        encoding: text.encoding,
+        bom: text.bom,
+    }];
+    transform(&mut opts, &mut inputs)?;
+    let groups = compute_groups(&opts, &mut inputs);
+    write_inputs(&opts, &inputs, &groups, out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(spec: &str) -> String {
+        parse_positioning(spec).unwrap().to_string()
+    }
+
+    #[test]
+    fn positioning() {
+        assert_eq!(round_trip("<10>"), "<10>");
+        assert_eq!(round_trip("08>"), "08>");
+        assert_eq!(round_trip("00>"), "00>");
+        assert_eq!(round_trip("<10-20="), "<10-20=");
+        assert_eq!(round_trip("0-6<"), "0-6<");
+        assert_eq!(round_trip("05-10>"), "05-10>");
+        assert!(parse_positioning("0<").is_ok());
+        assert!(parse_positioning("0<0>").is_ok());
+        assert!(parse_positioning("08<").is_err());
+        assert!(parse_positioning("10-5<").is_err());
+        let p = parse_positioning("05-10>").unwrap();
+        assert!(p.zero_padded(0));
+        assert_eq!((p.min_width(0), p.max_width(0)), (5, Some(10)));
+        assert!(!parse_positioning("0-6>").unwrap().zero_padded(0));
+    }
+
+    #[test]
+    fn positioning_builder() {
+        let p = Positioning::column(1)
+            .right()
+            .min_width(20)
+            .build()
+            .unwrap();
+        assert_eq!(p.to_string(), "<20><");
+        let p = Positioning::column(0).zero_padded().build().unwrap();
+        let parsed = parse_positioning(&p.to_string()).unwrap();
+        assert!(parsed.zero_padded(0));
+        assert_eq!(parsed.alignment(0), Right);
+        let p = Positioning::column(0).min_width(10).max_width(5).build();
+        assert!(p.is_err());
+        let p = Positioning::column(2).min_width(20).build().unwrap();
+        assert_eq!(round_trip(&p.to_string()), p.to_string());
+    }
+
+    #[test]
+    fn align_text() {
+        let config = AlignConfig::default();
+        assert_eq!(align(&config, "a bb\nccc d\n").unwrap(), "a   bb\nccc d\n");
+        let lines = align_lines(["a bb".to_string(), "ccc d".to_string()], &config).unwrap();
+        assert_eq!(lines.collect::<Vec<_>>(), ["a   bb", "ccc d"]);
+    }
+
+    /// A reader that always fails.
+    struct Broken;
+
+    impl std::io::Read for Broken {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("broken"))
+        }
+    }
+
+    #[test]
+    fn read_error() {
+        let input = std::io::BufReader::new(std::io::Read::chain(&b"a 1\n"[..], Broken));
+        let err = align_stream(&AlignConfig::default(), input, Vec::new()).unwrap_err();
+        assert!(matches!(err, AlignError::Io { .. }));
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let input = &b"a 1\n\xffb 22\nc 3\n"[..];
+        let text = read_as_unicode(input, None, &AlignConfig::default(), &mut Vec::new()).unwrap();
+        let lines: Vec<&str> = text.lines.iter().map(|l| &**l).collect();
+        assert_eq!(lines, ["a 1", "\u{fffd}b 22", "c 3"]);
+    }
+
+    #[test]
+    fn config_serde() {
+        let json = r#"{"out-sep": " | ", "positioning": "<3>"}"#;
+        let config: AlignConfig = serde_json::from_str(json).unwrap();
+        let json = serde_json::to_string(&config).unwrap();
+        let config: AlignConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(config.out_sep, " | ");
+        assert_eq!(config.positioning.to_string(), "<3>");
+        assert_eq!(align(&config, "a b\n").unwrap(), "a |   b\n");
+    }
+
+    #[test]
+    fn join_brackets() {
+        let config = AlignConfig {
+            join_brackets: true,
+            ..AlignConfig::default()
+        };
+        let text = "f(a,\n  b) = 1\nlonger = 2\n";
+        let aligned = "f(a,\n  b)   = 1\nlonger = 2\n";
+        assert_eq!(align(&config, text).unwrap(), aligned);
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn config_default() {
+        use clap::Parser;
+        let cli = crate::Cli::parse_from(["align"]);
+        let parsed = serde_json::to_value(&cli.config).unwrap();
+        let default = serde_json::to_value(AlignConfig::default()).unwrap();
+        assert_eq!(parsed, default);
+    }
+
+    #[test]
+    fn diagnostics() {
+        let config = AlignConfig {
+            max_line_bytes: Some(4),
+            long_lines: LongLines::Skip,
+            ..AlignConfig::default()
+        };
+        let mut out = Vec::new();
+        let log = align_stream(
+            &config,
+            &b"a 1
+bbbbb 2
+"[..],
+            &mut out,
+        )
+        .unwrap();
+        assert_eq!(out, b"a 1\n");
+        assert_eq!(
+            log,
+            [Diagnostic::Warning(
+                "skipping line 2, which is longer than 4 bytes".to_string()
+            )]
+        );
+    }
+}
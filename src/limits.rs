@@ -0,0 +1,48 @@
+use {
+    crate::AlignError,
+    std::{
+        fs,
+        path::{Path, PathBuf},
+    },
+};
+
+/// The estimated memory needed per byte of input, for the lines and their cells.
+const MEMORY_PER_BYTE: u64 = 4;
+
+/// The number of lines whose widths are used if the input does not fit into
+/// `--max-memory`.
+pub const STREAM_SAMPLE: usize = 1000;
+
+/// Parses a size in bytes such as `4096`, `64K`, `512M`, or `2G`.
+///
+/// The suffixes are powers of 1024.
+pub fn parse_size(s: &str) -> Result<u64, AlignError> {
+    let invalid = || AlignError::Value(format!("Invalid size `{s}`"));
+    let (digits, unit) = match s.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+        Some((i, _)) => s.split_at(i),
+        None => (s, ""),
+    };
+    let shift = match unit.to_ascii_uppercase().trim_end_matches(['B', 'I']) {
+        "" => 0,
+        "K" => 10,
+        "M" => 20,
+        "G" => 30,
+        "T" => 40,
+        _ => return Err(invalid()),
+    };
+    let n: u64 = digits.parse().map_err(|_| invalid())?;
+    n.checked_mul(1 << shift).ok_or_else(invalid)
+}
+
+/// Estimates the memory needed to align the files.
+///
+/// Compressed files count with their compressed size. stdin counts as empty since its size
+/// is not known in advance.
+pub fn estimate(files: &[PathBuf]) -> u64 {
+    let size = |path: &Path| match path.as_os_str() == "-" {
+        true => 0,
+        false => fs::metadata(path).map_or(0, |m| m.len()),
+    };
+    let bytes: u64 = files.iter().map(|p| size(p)).sum();
+    bytes.saturating_mul(MEMORY_PER_BYTE)
+}
@@ -0,0 +1,39 @@
+use std::fmt::{self, Display, Formatter};
+
+/// A message about the alignment that does not stop it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Diagnostic {
+    /// A problem with the input, such as lines that are indented inconsistently.
+    Warning(String),
+    /// A description of what is done, such as the detected separator. The command line
+    /// interface prints it if `-v` is given at least `level` times.
+    Info { level: u8, message: String },
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Diagnostic::Warning(message) => write!(f, "warning: {message}"),
+            Diagnostic::Info { message, .. } => write!(f, "info: {message}"),
+        }
+    }
+}
+
+/// Adds a warning to the diagnostics in `$log`.
+macro_rules! warning {
+    ($log:expr, $($arg:tt)*) => {
+        $log.push($crate::Diagnostic::Warning(format!($($arg)*)))
+    };
+}
+pub(crate) use warning;
+
+/// Adds a message for `-v` given at least `level` times to the diagnostics in `$log`.
+macro_rules! info {
+    ($log:expr, $level:expr, $($arg:tt)*) => {
+        $log.push($crate::Diagnostic::Info {
+            level: $level,
+            message: format!($($arg)*),
+        })
+    };
+}
+pub(crate) use info;
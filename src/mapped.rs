@@ -0,0 +1,154 @@
+use std::{borrow::Borrow, ops::Deref, sync::Arc};
+#[cfg(feature = "cli")]
+use {
+    crate::{decompress, AlignConfig, Text},
+    std::{fs::File, path::Path},
+};
+
+/// A memory-mapped file that contains valid UTF-8.
+pub struct Map(memmap2::Mmap);
+
+impl Map {
+    fn as_str(&self) -> &str {
+        // SAFETY: The map is only created for files that are valid UTF-8.
+        unsafe { std::str::from_utf8_unchecked(&self.0) }
+    }
+}
+
+/// The text of a line.
+///
+/// The lines of memory-mapped files point into the map until they are changed.
+#[derive(Clone)]
+pub enum LineText {
+    Owned(String),
+    #[cfg_attr(not(feature = "cli"), allow(dead_code))]
+    Mapped {
+        map: Arc<Map>,
+        start: usize,
+        end: usize,
+    },
+}
+
+impl LineText {
+    /// Returns the text for modification, copying it out of the map first if necessary.
+    pub fn to_mut(&mut self) -> &mut String {
+        if let LineText::Mapped { .. } = self {
+            *self = LineText::Owned(self.to_string());
+        }
+        match self {
+            LineText::Owned(s) => s,
+            LineText::Mapped { .. } => unreachable!(),
+        }
+    }
+}
+
+impl Deref for LineText {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        match self {
+            LineText::Owned(s) => s,
+            LineText::Mapped { map, start, end } => &map.as_str()[*start..*end],
+        }
+    }
+}
+
+impl Borrow<str> for LineText {
+    fn borrow(&self) -> &str {
+        self
+    }
+}
+
+impl From<String> for LineText {
+    fn from(s: String) -> Self {
+        LineText::Owned(s)
+    }
+}
+
+/// Reads the lines of a regular file through a memory map, so that they are not copied.
+///
+/// Returns `None` if the file cannot be mapped or has to be decompressed, decoded, or
+/// cut into shorter lines. It is then read like any other input.
+#[cfg(feature = "cli")]
+pub fn read(path: &Path, file: &File, opts: &AlignConfig) -> Option<Text> {
+    if opts.encoding.is_some() || opts.max_line_bytes.is_some() {
+        return None;
+    }
+    if !file.metadata().ok()?.is_file() {
+        return None;
+    }
+    // SAFETY: Files that are changed by other processes while they are aligned are not
+    // supported.
+    let map = unsafe { memmap2::Mmap::map(file) }.ok()?;
+    let format = decompress::select(opts.decompress, Some(path), || Ok(&map[..]));
+    if !matches!(format, Ok(None)) {
+        return None;
+    }
+    let text = std::str::from_utf8(&map).ok()?;
+    let bom = text.starts_with('\u{feff}');
+    let delim = opts.record_delim() as char;
+    let mut ranges = Vec::new();
+    let mut start = match bom {
+        true => '\u{feff}'.len_utf8(),
+        false => 0,
+    };
+    for line in text[start..].split_terminator(delim) {
+        let end = start + line.len();
+        match line.strip_suffix('\r').filter(|_| delim == '\n') {
+            Some(line) => ranges.push((start, start + line.len())),
+            None => ranges.push((start, end)),
+        }
+        start = end + 1;
+    }
+    let map = Arc::new(Map(map));
+    let lines = ranges.into_iter().map(|(start, end)| LineText::Mapped {
+        map: map.clone(),
+        start,
+        end,
+    });
+    Some(Text {
+        lines: lines.collect(),
+        encoding: encoding_rs::UTF_8,
+        bom,
+    })
+}
+
+#[cfg(all(test, feature = "cli"))]
+mod tests {
+    use {super::*, std::io::Write};
+
+    /// Maps a file with the given contents.
+    fn map(name: &str, contents: &[u8]) -> Option<Text> {
+        let path = std::env::temp_dir().join(format!("align-{name}-{}", std::process::id()));
+        File::create(&path).unwrap().write_all(contents).unwrap();
+        let text = read(&path, &File::open(&path).unwrap(), &AlignConfig::default());
+        std::fs::remove_file(&path).unwrap();
+        text
+    }
+
+    #[test]
+    fn lines() {
+        let text = map("lines", "\u{feff}a b\r\n\nc d".as_bytes()).unwrap();
+        assert!(text.bom);
+        let lines: Vec<&str> = text.lines.iter().map(|l| &**l).collect();
+        assert_eq!(lines, ["a b", "", "c d"]);
+        assert!(text
+            .lines
+            .iter()
+            .all(|l| matches!(l, LineText::Mapped { .. })));
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        assert!(map("invalid", b"a\n\xff\n").is_none());
+    }
+
+    #[test]
+    fn to_mut() {
+        let mut text = map("to-mut", b"a b\n").unwrap();
+        let line = &mut text.lines[0];
+        line.to_mut().push_str(" c");
+        assert_eq!(&**line, "a b c");
+        assert!(matches!(line, LineText::Owned(_)));
+    }
+}
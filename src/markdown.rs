@@ -0,0 +1,201 @@
+use crate::{trim, AlignConfig, Alignment, Input, Line, Positioning, Rule, Words};
+
+/// Splits a row of a Markdown table at the unescaped pipes. The outer pipes are removed.
+///
+/// Returns `None` if the line contains no pipe.
+pub fn split_row(line: &[u8]) -> Option<Vec<(usize, usize)>> {
+    let mut pipes = Vec::new();
+    let mut esc = false;
+    for (i, &c) in line.iter().enumerate() {
+        if c == b'|' && !esc {
+            pipes.push(i);
+        }
+        esc = !esc && c == b'\\';
+    }
+    if pipes.is_empty() {
+        return None;
+    }
+    let (mut start, mut end) = trim(line, 0, line.len());
+    if pipes[0] == start {
+        start = pipes.remove(0) + 1;
+    }
+    if pipes.last().is_some_and(|&p| p + 1 == end) {
+        end = pipes.pop().unwrap();
+    }
+    let mut words = Vec::new();
+    let mut pos = start;
+    for pipe in pipes {
+        words.push(trim(line, pos, pipe));
+        pos = pipe + 1;
+    }
+    words.push(trim(line, pos, end));
+    Some(words)
+}
+
+/// Returns the alignment of a cell of the delimiter row, such as `:---:`.
+///
+/// Returns `None` if the cell is not part of a delimiter row.
+fn parse_delimiter(cell: &str) -> Option<Option<Alignment>> {
+    let left = cell.starts_with(':');
+    let right = cell.len() > 1 && cell.ends_with(':');
+    let dashes = &cell[usize::from(left)..cell.len() - usize::from(right)];
+    if dashes.is_empty() || dashes.bytes().any(|c| c != b'-') {
+        return None;
+    }
+    Some(match (left, right) {
+        (true, true) => Some(Alignment::Centered),
+        (true, false) => Some(Alignment::Left),
+        (false, true) => Some(Alignment::Right),
+        (false, false) => None,
+    })
+}
+
+/// Returns whether the cells form the delimiter row of a Markdown table.
+pub fn is_delimiter_row(cells: &[&str]) -> bool {
+    cells.len() > 0 && cells.iter().all(|c| parse_delimiter(c).is_some())
+}
+
+/// Applies the alignments of the delimiter rows to the columns that have no explicit
+/// alignment and prepares the tables for the output.
+///
+/// With --to-markdown, a delimiter row is added below the first row of inputs without
+/// one and pipes inside cells are escaped. Otherwise, the delimiter rows are removed and
+/// the pipes inside cells are unescaped.
+pub fn prepare(opts: &AlignConfig, inputs: &mut [Input], groups: &mut [Positioning]) {
+    let explicit = opts.positioning.align.vec.len();
+    for input in inputs.iter_mut() {
+        for line in &input.lines {
+            if line.rule != Some(Rule::Markdown) {
+                continue;
+            }
+            for (i, cell) in line.words.iter().enumerate().skip(explicit) {
+                if let Some(Some(align)) = parse_delimiter(cell) {
+                    groups[line.group].align.set(i, align);
+                }
+            }
+        }
+        if !opts.to_markdown {
+            input.lines.retain(|l| l.rule != Some(Rule::Markdown));
+            for line in &mut input.lines {
+                line.words
+                    .map(|_, cell| cell.contains("\\|").then(|| cell.replace("\\|", "|")));
+            }
+            continue;
+        }
+        if !opts.from_markdown {
+            for line in &mut input.lines {
+                line.words
+                    .map(|_, cell| cell.contains('|').then(|| escape(cell)));
+            }
+        }
+        let has_delimiter = input.lines.iter().any(|l| l.rule == Some(Rule::Markdown));
+        let first = input.lines.iter().position(|l| l.words.words.len() > 0);
+        if let (false, Some(first)) = (has_delimiter, first) {
+            let group = input.lines[first].group;
+            let columns = input
+                .lines
+                .iter()
+                .filter(|l| l.group == group)
+                .map(|l| l.words.words.len())
+                .max()
+                .unwrap_or(0);
+            input.lines.insert(
+                first + 1,
+                Line {
+                    words: Words::from_cells(&vec!["---".to_string(); columns]),
+                    group,
+                    header: false,
+                    rule: Some(Rule::Markdown),
+                },
+            );
+        }
+        for line in &mut input.lines {
+            if line.rule == Some(Rule::Markdown) {
+                // An explicit left marker is kept, since it is not the default everywhere.
+                line.words.map(|_, cell| match parse_delimiter(cell) {
+                    Some(Some(Alignment::Left)) => Some(":--".to_string()),
+                    _ => Some("---".to_string()),
+                });
+            }
+        }
+    }
+}
+
+/// Escapes the unescaped pipes of a cell.
+fn escape(cell: &str) -> String {
+    let mut res = String::new();
+    let mut esc = false;
+    for c in cell.chars() {
+        if c == '|' && !esc {
+            res.push('\\');
+        }
+        esc = !esc && c == '\\';
+        res.push(c);
+    }
+    res
+}
+
+/// Fills the delimiter rows with dashes as wide as the columns and marks the alignments
+/// of the columns with colons. Left aligned columns are marked if their cell of the
+/// delimiter row was.
+pub fn draw_delimiters(inputs: &mut [Input], groups: &[Positioning]) {
+    for line in inputs.iter_mut().flat_map(|i| &mut i.lines) {
+        if line.rule != Some(Rule::Markdown) {
+            continue;
+        }
+        let positioning = &groups[line.group];
+        line.words.map(|i, cell| {
+            let width = positioning.max_width.get(i).max(3);
+            let (left, right) = match positioning.align.get(i) {
+                Alignment::Right | Alignment::Decimal | Alignment::Path => ("", ":"),
+                Alignment::Centered => (":", ":"),
+                Alignment::Left if cell.starts_with(':') => (":", ""),
+                Alignment::Left | Alignment::Justified => ("", ""),
+            };
+            let dashes = "-".repeat(width - left.len() - right.len());
+            Some(format!("{left}{dashes}{right}"))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::AlignConfig};
+
+    #[test]
+    fn delimiters() {
+        assert_eq!(parse_delimiter(":---:"), Some(Some(Alignment::Centered)));
+        assert_eq!(parse_delimiter(":--"), Some(Some(Alignment::Left)));
+        assert_eq!(parse_delimiter("--:"), Some(Some(Alignment::Right)));
+        assert_eq!(parse_delimiter("-"), Some(None));
+        assert_eq!(parse_delimiter(":"), None);
+        assert_eq!(parse_delimiter("a-"), None);
+        assert!(is_delimiter_row(&["---", ":-:"]));
+        assert!(!is_delimiter_row(&["---", "a"]));
+    }
+
+    #[test]
+    fn split() {
+        let line = br"| a | b \| c |";
+        let words = split_row(line).unwrap();
+        let cells: Vec<_> = words.iter().map(|&(s, e)| &line[s..e]).collect();
+        assert_eq!(cells, [&b"a"[..], br"b \| c"]);
+        assert!(split_row(b"text").is_none());
+    }
+
+    #[test]
+    fn round_trip() {
+        let config = AlignConfig {
+            from_markdown: true,
+            to_markdown: true,
+            ..AlignConfig::default()
+        };
+        let text = "| a | b | c |\n|:--|--:|---|\n| xxxx | y | z |\n";
+        let aligned = "\
+| a    |   b | c   |
+| :--- | --: | --- |
+| xxxx |   y | z   |
+";
+        assert_eq!(crate::align(&config, text).unwrap(), aligned);
+    }
+}
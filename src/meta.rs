@@ -0,0 +1,74 @@
+use {
+    crate::{infer, AlignConfig, Alignment, Input, Positioning},
+    anyhow::{Context, Error},
+    serde::Serialize,
+    std::{fs, path::Path},
+};
+
+/// The layout written by `--emit-meta`.
+#[derive(Serialize)]
+struct Meta {
+    /// The number of rows with cells, not counting headers and rules.
+    rows: usize,
+    groups: Vec<Group>,
+}
+
+#[derive(Serialize)]
+struct Group {
+    columns: Vec<Column>,
+}
+
+#[derive(Serialize)]
+struct Column {
+    width: usize,
+    align: &'static str,
+    #[serde(rename = "type")]
+    ty: String,
+}
+
+fn align_name(align: Alignment) -> &'static str {
+    match align {
+        Alignment::Left => "left",
+        Alignment::Right => "right",
+        Alignment::Centered => "center",
+        Alignment::Decimal => "decimal",
+        Alignment::Path => "path",
+        Alignment::Justified => "justified",
+    }
+}
+
+/// Writes the widths, alignments, and types of the columns of each group as JSON.
+pub fn emit(
+    path: &Path,
+    opts: &AlignConfig,
+    inputs: &[Input],
+    groups: &[Positioning],
+) -> Result<(), Error> {
+    let lines = || inputs.iter().flat_map(|i| &i.lines);
+    let types = infer::infer(opts, inputs);
+    let mut columns = vec![0; groups.len()];
+    for line in lines() {
+        columns[line.group] = columns[line.group].max(line.words.words.len());
+    }
+    let meta = Meta {
+        rows: lines()
+            .filter(|l| !l.header && l.rule.is_none() && l.words.words.len() > 0)
+            .count(),
+        groups: groups
+            .iter()
+            .zip(columns)
+            .map(|(positioning, columns)| Group {
+                columns: (0..columns)
+                    .map(|i| Column {
+                        width: positioning.max_width.get(i),
+                        align: align_name(positioning.align.get(i)),
+                        ty: types.get(i).map(|t| t.to_string()).unwrap_or_default(),
+                    })
+                    .collect(),
+            })
+            .collect(),
+    };
+    let json = serde_json::to_vec_pretty(&meta)?;
+    fs::write(path, json)
+        .with_context(|| format!("Could not write the layout to {}", path.display()))
+}
@@ -0,0 +1,43 @@
+use {
+    anyhow::{Context, Error},
+    std::{
+        env,
+        io::{stdout, IsTerminal, Write},
+        process::{Command, Stdio},
+    },
+};
+
+/// The pager used if `PAGER` is not set. `-S` chops long lines instead of wrapping them.
+const DEFAULT_PAGER: &str = "less -S";
+
+/// Returns the number of lines of the terminal.
+fn screen_height() -> Option<usize> {
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some((_, terminal_size::Height(height))) = terminal_size::terminal_size() {
+        return Some(height as usize);
+    }
+    env::var("LINES").ok()?.parse().ok()
+}
+
+/// Prints the output, through the pager if stdout is a terminal and the output does not
+/// fit on the screen.
+pub fn page(output: &[u8]) -> Result<(), Error> {
+    let lines = output.iter().filter(|&&c| c == b'\n').count();
+    let fits = screen_height().is_some_and(|height| lines < height);
+    if !stdout().is_terminal() || fits {
+        return Ok(stdout().lock().write_all(output)?);
+    }
+    let pager = env::var("PAGER").unwrap_or_else(|_| DEFAULT_PAGER.to_string());
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(&pager)
+        .stdin(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Could not start the pager `{pager}`"))?;
+    let mut stdin = child.stdin.take().unwrap();
+    // The pager closes its stdin when the user quits early.
+    let _ = stdin.write_all(output);
+    drop(stdin);
+    child.wait()?;
+    Ok(())
+}
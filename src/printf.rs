@@ -0,0 +1,234 @@
+use {
+    crate::{number_value, string_serde, AlignConfig, AlignError, Input},
+    serde::{Deserialize, Deserializer, Serialize, Serializer},
+    std::{
+        fmt::{self, Display, Formatter},
+        num::NonZeroUsize,
+    },
+};
+
+/// A printf-style format of the numbers of a column.
+#[derive(Clone, Debug)]
+pub struct ColumnFormat {
+    /// The column, starting at 1.
+    pub column: NonZeroUsize,
+    source: String,
+    prefix: String,
+    suffix: String,
+    spec: Spec,
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+struct Spec {
+    left: bool,
+    plus: bool,
+    space: bool,
+    zero: bool,
+    alt: bool,
+    width: usize,
+    precision: Option<usize>,
+    conversion: char,
+}
+
+impl Display for ColumnFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.column, self.source)
+    }
+}
+
+string_serde!(ColumnFormat, parse_column_format);
+
+/// Parses a column and a format such as `3=%08.2f`.
+pub fn parse_column_format(s: &str) -> Result<ColumnFormat, AlignError> {
+    let invalid = |msg: &str| AlignError::Value(format!("Invalid format `{s}`: {msg}"));
+    let (column, format) = s
+        .split_once('=')
+        .ok_or_else(|| invalid("expected `column=format`"))?;
+    let column = column
+        .parse()
+        .map_err(|_| invalid("expected a column starting at 1"))?;
+    let mut prefix = String::new();
+    let mut suffix = String::new();
+    let mut spec = None;
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        let text = match spec {
+            Some(_) => &mut suffix,
+            None => &mut prefix,
+        };
+        if c != '%' {
+            text.push(c);
+            continue;
+        }
+        if chars.next_if_eq(&'%').is_some() {
+            text.push('%');
+            continue;
+        }
+        if spec.is_some() {
+            return Err(invalid("expected a single conversion"));
+        }
+        let mut s = Spec::default();
+        while let Some(flag) = chars.next_if(|c| "-+ 0#".contains(*c)) {
+            match flag {
+                '-' => s.left = true,
+                '+' => s.plus = true,
+                ' ' => s.space = true,
+                '0' => s.zero = true,
+                _ => s.alt = true,
+            }
+        }
+        let number = |chars: &mut std::iter::Peekable<std::str::Chars<'_>>| {
+            let mut n = String::new();
+            while let Some(d) = chars.next_if(char::is_ascii_digit) {
+                n.push(d);
+            }
+            n.parse().unwrap_or(0)
+        };
+        s.width = number(&mut chars);
+        if chars.next_if_eq(&'.').is_some() {
+            s.precision = Some(number(&mut chars));
+        }
+        s.conversion = match chars.next() {
+            Some(c @ ('d' | 'i' | 'x' | 'X' | 'o' | 'f' | 'F' | 'e' | 'E')) => c,
+            _ => return Err(invalid("expected one of d, i, x, X, o, f, F, e, or E")),
+        };
+        spec = Some(s);
+    }
+    Ok(ColumnFormat {
+        column,
+        source: format.to_string(),
+        prefix,
+        suffix,
+        spec: spec.ok_or_else(|| invalid("expected a conversion"))?,
+    })
+}
+
+impl Spec {
+    fn format(self, value: f64, decimal: char) -> String {
+        let negative = value < 0.0 || (value == 0.0 && value.is_sign_negative());
+        let int = value.round() as i64;
+        let (mut prefix, digits) = match self.conversion {
+            'd' | 'i' => {
+                let digits = int.unsigned_abs().to_string();
+                let min = self.precision.unwrap_or(0);
+                (
+                    String::new(),
+                    format!("{}{digits}", "0".repeat(min.saturating_sub(digits.len()))),
+                )
+            }
+            'x' if self.alt => ("0x".to_string(), format!("{int:x}")),
+            'X' if self.alt => ("0X".to_string(), format!("{int:X}")),
+            'o' if self.alt => ("0".to_string(), format!("{int:o}")),
+            'x' => (String::new(), format!("{int:x}")),
+            'X' => (String::new(), format!("{int:X}")),
+            'o' => (String::new(), format!("{int:o}")),
+            'e' | 'E' => {
+                let res = exponential(value.abs(), self.precision.unwrap_or(6));
+                let res = match self.conversion {
+                    'E' => res.to_uppercase(),
+                    _ => res,
+                };
+                (String::new(), res.replace('.', &decimal.to_string()))
+            }
+            _ => {
+                let res = format!("{:.*}", self.precision.unwrap_or(6), value.abs());
+                (String::new(), res.replace('.', &decimal.to_string()))
+            }
+        };
+        // Hexadecimal and octal numbers are unsigned.
+        if matches!(self.conversion, 'd' | 'i' | 'f' | 'F' | 'e' | 'E') {
+            let sign = match (negative, self.plus, self.space) {
+                (true, _, _) => "-",
+                (false, true, _) => "+",
+                (false, false, true) => " ",
+                _ => "",
+            };
+            prefix.insert_str(0, sign);
+        }
+        let len = prefix.chars().count() + digits.chars().count();
+        let pad = self.width.saturating_sub(len);
+        // Zeros are ignored for integers with a precision, as in C.
+        let integer = matches!(self.conversion, 'd' | 'i' | 'x' | 'X' | 'o');
+        let zero = self.zero && !self.left && !(integer && self.precision.is_some());
+        match (self.left, zero) {
+            (true, _) => format!("{prefix}{digits}{}", " ".repeat(pad)),
+            (false, true) => format!("{prefix}{}{digits}", "0".repeat(pad)),
+            (false, false) => format!("{}{prefix}{digits}", " ".repeat(pad)),
+        }
+    }
+}
+
+/// Formats a non-negative number like `%e` in C, e.g. `1.500000e+02`.
+fn exponential(value: f64, precision: usize) -> String {
+    let res = format!("{value:.precision$e}");
+    let (mantissa, exp) = res.split_once('e').unwrap_or((&res, "0"));
+    let exp: i32 = exp.parse().unwrap_or(0);
+    let sign = if exp < 0 { '-' } else { '+' };
+    format!("{mantissa}e{sign}{:02}", exp.abs())
+}
+
+/// Formats the numbers of the column. All other cells are not changed.
+pub fn format(opts: &AlignConfig, format: &ColumnFormat, inputs: &mut [Input]) {
+    let column = format.column.get() - 1;
+    let decimal = opts.decimal();
+    for line in inputs.iter_mut().flat_map(|i| &mut i.lines) {
+        if line.header || line.rule.is_some() {
+            continue;
+        }
+        line.words.map(|i, cell| {
+            let value = number_value(cell, decimal).filter(|_| i == column)?;
+            let number = format.spec.format(value, decimal);
+            Some(format!("{}{number}{}", format.prefix, format.suffix))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(format: &str, value: f64) -> String {
+        let format = parse_column_format(&format!("1={format}")).unwrap();
+        format.spec.format(value, '.')
+    }
+
+    #[test]
+    fn parse() {
+        let format = parse_column_format("3=%08.2f%%").unwrap();
+        assert_eq!(format.column.get(), 3);
+        assert_eq!(format.suffix, "%");
+        assert!(format.spec.zero);
+        assert_eq!((format.spec.width, format.spec.precision), (8, Some(2)));
+        assert_eq!(format.to_string(), "3=%08.2f%%");
+        let format = parse_column_format("1=$%-+ #5d USD").unwrap();
+        assert_eq!((&*format.prefix, &*format.suffix), ("$", " USD"));
+        assert!(format.spec.left && format.spec.plus && format.spec.space && format.spec.alt);
+        for invalid in ["%d", "0=%d", "1=", "1=%", "1=%s", "1=%d %d", "1=100%"] {
+            assert!(parse_column_format(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(format("%d", 41.6), "42");
+        assert_eq!(format("%5d", -3.0), "   -3");
+        assert_eq!(format("%-5d", 3.0), "3    ");
+        assert_eq!(format("%05d", -3.0), "-0003");
+        assert_eq!(format("%+.3d", 7.0), "+007");
+        assert_eq!(format("%08.3d", 7.0), "     007");
+        assert_eq!(format("%#x", 255.0), "0xff");
+        assert_eq!(format("%X", 255.0), "FF");
+        assert_eq!(format("%#o", 8.0), "010");
+        assert_eq!(format("%.2f", 2.345), "2.35");
+        assert_eq!(format("% f", 1.0), " 1.000000");
+        assert_eq!(format("%.1f", -0.04), "-0.0");
+        assert_eq!(format("%e", 150.0), "1.500000e+02");
+        assert_eq!(format("%.1E", 0.00123), "1.2E-03");
+    }
+
+    #[test]
+    fn decimal_comma() {
+        let format = parse_column_format("1=%.1f").unwrap();
+        assert_eq!(format.spec.format(1.25, ','), "1,2");
+    }
+}
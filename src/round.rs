@@ -0,0 +1,126 @@
+use {
+    crate::{number_value, string_serde, AlignConfig, AlignError, Input},
+    serde::{Deserialize, Deserializer, Serialize, Serializer},
+    std::{
+        fmt::{self, Display, Formatter},
+        num::NonZeroUsize,
+    },
+};
+
+/// The rounding of the numbers of a column.
+#[derive(Copy, Clone, Debug)]
+pub struct Rounding {
+    /// The column, starting at 1.
+    pub column: NonZeroUsize,
+    pub digits: usize,
+    /// Whether `digits` is the number of significant digits instead of the number of
+    /// digits after the decimal separator.
+    pub significant: bool,
+}
+
+impl Display for Rounding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.column, self.digits)?;
+        if self.significant {
+            f.write_str("sig")?;
+        }
+        Ok(())
+    }
+}
+
+string_serde!(Rounding, parse_rounding);
+
+/// Parses a rounding such as `4=2` or `2=3sig`.
+pub fn parse_rounding(s: &str) -> Result<Rounding, AlignError> {
+    let invalid = || AlignError::Value(format!("Expected `column=digits[sig]`, found `{s}`"));
+    let (column, digits) = s.split_once('=').ok_or_else(invalid)?;
+    let (digits, significant) = match digits.strip_suffix("sig") {
+        Some(digits) => (digits, true),
+        None => (digits, false),
+    };
+    let rounding = Rounding {
+        column: column.parse().map_err(|_| invalid())?,
+        digits: digits.parse().map_err(|_| invalid())?,
+        significant,
+    };
+    if significant && rounding.digits == 0 {
+        return Err(AlignError::Value(
+            "At least 1 significant digit is required".to_string(),
+        ));
+    }
+    Ok(rounding)
+}
+
+impl Rounding {
+    fn round(self, value: f64) -> String {
+        let res = self.format(value);
+        // Numbers that round to zero lose their sign.
+        match res.strip_prefix('-') {
+            Some(rest) if rest.bytes().all(|c| c == b'0' || c == b'.') => rest.to_string(),
+            _ => res,
+        }
+    }
+
+    fn format(self, value: f64) -> String {
+        if !self.significant {
+            return format!("{:.*}", self.digits, value);
+        }
+        if value == 0.0 || !value.is_finite() {
+            return value.to_string();
+        }
+        let magnitude = value.abs().log10().floor() as i32;
+        let decimals = self.digits as i32 - 1 - magnitude;
+        match usize::try_from(decimals) {
+            Ok(decimals) => format!("{value:.decimals$}"),
+            Err(_) => {
+                let unit = 10f64.powi(-decimals);
+                format!("{:.0}", (value / unit).round() * unit)
+            }
+        }
+    }
+}
+
+/// Rounds the numbers of the column. All other cells are not changed.
+pub fn round(opts: &AlignConfig, rounding: Rounding, inputs: &mut [Input]) {
+    let column = rounding.column.get() - 1;
+    let decimal = opts.decimal();
+    for line in inputs.iter_mut().flat_map(|i| &mut i.lines) {
+        if line.header || line.rule.is_some() {
+            continue;
+        }
+        line.words.map(|i, cell| {
+            let value = number_value(cell, decimal).filter(|_| i == column)?;
+            Some(rounding.round(value).replace('.', &decimal.to_string()))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let rounding = parse_rounding("4=2").unwrap();
+        assert_eq!((rounding.column.get(), rounding.digits), (4, 2));
+        assert!(!rounding.significant);
+        assert_eq!(parse_rounding("2=3sig").unwrap().to_string(), "2=3sig");
+        for invalid in ["4", "0=2", "x=2", "4=", "4=0sig", "4=2dig"] {
+            assert!(parse_rounding(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn round() {
+        let fixed = parse_rounding("1=2").unwrap();
+        assert_eq!(fixed.round(1.23456), "1.23");
+        assert_eq!(fixed.round(-2.005), "-2.00");
+        assert_eq!(fixed.round(-0.004), "0.00");
+        let whole = parse_rounding("1=0").unwrap();
+        assert_eq!(whole.round(-0.4), "0");
+        let significant = parse_rounding("1=2sig").unwrap();
+        assert_eq!(significant.round(0.012345), "0.012");
+        assert_eq!(significant.round(12345.0), "12000");
+        assert_eq!(significant.round(-0.0), "0");
+    }
+}
@@ -0,0 +1,194 @@
+use {
+    crate::{
+        cli::{decompressed, Cli},
+        compute_groups, decompress,
+        editorconfig::EditorConfig,
+        group_lines, parse_lines, transform, write_inputs, AlignConfig, Blank, Input, Positioning,
+    },
+    anyhow::{bail, Context, Error},
+    std::{
+        fs::File,
+        io::{stdin, stdout, BufRead, BufReader, Write},
+    },
+};
+
+/// Reads the next line without its line ending.
+///
+/// A byte order mark at the start of the first line is removed.
+fn next_line(
+    input: &mut impl BufRead,
+    delim: u8,
+    first: bool,
+    buf: &mut Vec<u8>,
+) -> Result<Option<String>, Error> {
+    buf.clear();
+    if input.read_until(delim, buf)? == 0 {
+        return Ok(None);
+    }
+    let mut line = buf.strip_suffix(&[delim]).unwrap_or(buf);
+    if delim == b'\n' {
+        line = line.strip_suffix(b"\r").unwrap_or(line);
+    }
+    let line = String::from_utf8_lossy(line);
+    match line.strip_prefix('\u{feff}').filter(|_| first) {
+        Some(rest) => Ok(Some(rest.to_string())),
+        None => Ok(Some(line.into_owned())),
+    }
+}
+
+/// Aligns the input using the column widths of its first `n` lines.
+///
+/// The following lines are printed as soon as they have been read. Their cells overflow
+/// the columns if they are wider.
+pub fn sample(cli: &mut Cli, n: usize) -> Result<(), Error> {
+    let verbosity = cli.verbosity();
+    let opts = &mut cli.config;
+    let (mut input, mut config) = match &cli.files[..] {
+        [] => (
+            decompressed(Box::new(stdin().lock()), None, opts)?,
+            EditorConfig::default(),
+        ),
+        [path] if path.as_os_str() == "-" => (
+            decompressed(Box::new(stdin().lock()), None, opts)?,
+            EditorConfig::default(),
+        ),
+        [path] => {
+            let file =
+                File::open(path).with_context(|| format!("Could not open {}", path.display()))?;
+            let input = decompressed(Box::new(BufReader::new(file)), Some(path), opts)?;
+            let config = EditorConfig::of(&decompress::strip_extension(path))?;
+            (input, config)
+        }
+        _ => bail!("--sample accepts at most one file"),
+    };
+    if opts.null {
+        config.set_eol(b"\0");
+    }
+    let delim = opts.record_delim();
+    let mut buf = Vec::new();
+    let mut text = Vec::new();
+    while text.len() < n {
+        match next_line(&mut input, delim, text.is_empty(), &mut buf)? {
+            Some(line) => text.push(line),
+            None => break,
+        }
+    }
+    let first = text.is_empty();
+    let mut stdout = stdout().lock();
+    let mut align = |opts: &mut AlignConfig,
+                     text: Vec<String>,
+                     indent: Option<Vec<u8>>,
+                     sampled: Option<&Positioning>|
+     -> Result<(Option<Vec<u8>>, Positioning), Error> {
+        let mut log = Vec::new();
+        let parsed = parse_lines(opts, text, &mut log);
+        verbosity.report(&mut log);
+        let (own_indent, lines) = parsed?;
+        let mut inputs = [Input {
+            indent: indent.or(own_indent),
+            lines,
+            config: config.clone(),
+            encoding: encoding_rs::UTF_8,
+            bom: false,
+        }];
+        transform(opts, &mut inputs)?;
+        let mut groups = compute_groups(opts, &mut inputs);
+        if let Some(sampled) = sampled {
+            groups.fill(sampled.clone());
+        }
+        write_inputs(opts, &inputs, &groups, &mut stdout)?;
+        stdout.flush()?;
+        let [input] = inputs;
+        Ok((input.indent, groups.pop().unwrap()))
+    };
+    let mut context = block_context(&text);
+    let mut prev_blank = text.last().is_some_and(|l| is_blank(l));
+    let (indent, mut sampled) = align(opts, text, None, None)?;
+    // Only the first line of the input can be a header.
+    opts.header = false;
+    // With --map-cell, the lines are aligned in batches so that the commands do not run
+    // once per line.
+    let batch = match opts.map_cell.is_empty() {
+        true => 1,
+        false => n,
+    };
+    let mut pending = Vec::new();
+    while let Some(line) = next_line(&mut input, delim, first, &mut buf)? {
+        let blank = is_blank(&line);
+        if blank && prev_blank && matches!(opts.blank, Blank::Collapse) {
+            continue;
+        }
+        prev_blank = blank;
+        if blank {
+            context.push(line.clone());
+        } else if starts_group(opts, &context, &line)? {
+            if pending.len() > 0 {
+                align(
+                    opts,
+                    std::mem::take(&mut pending),
+                    indent.clone(),
+                    Some(&sampled),
+                )?;
+            }
+            // The new group gets the widths of its own first lines.
+            let mut text = vec![line];
+            while text.len() < n {
+                match next_line(&mut input, delim, false, &mut buf)? {
+                    Some(line) => text.push(line),
+                    None => break,
+                }
+            }
+            context = block_context(&text);
+            prev_blank = text.last().is_some_and(|l| is_blank(l));
+            sampled = align(opts, text, indent.clone(), None)?.1;
+            continue;
+        } else {
+            context = vec![line.clone()];
+        }
+        pending.push(line);
+        if pending.len() >= batch {
+            align(
+                opts,
+                std::mem::take(&mut pending),
+                indent.clone(),
+                Some(&sampled),
+            )?;
+        }
+    }
+    if pending.len() > 0 {
+        align(opts, pending, indent, Some(&sampled))?;
+    }
+    Ok(())
+}
+
+fn is_blank(line: &str) -> bool {
+    line.chars().all(char::is_whitespace)
+}
+
+/// Returns the last line that is not blank and the blank lines after it, which decide
+/// whether the next line starts a new group.
+fn block_context(text: &[String]) -> Vec<String> {
+    let start = text.iter().rposition(|l| !is_blank(l)).unwrap_or(0);
+    text[start..].to_vec()
+}
+
+/// Returns whether `line` starts a new group of columns after the lines of `context`.
+fn starts_group(opts: &AlignConfig, context: &[String], line: &str) -> Result<bool, Error> {
+    if context.is_empty() {
+        return Ok(false);
+    }
+    let mut text = context.to_vec();
+    text.push(line.to_string());
+    // The lines have already been checked when they were printed.
+    let (indent, lines) = parse_lines(opts, text, &mut Vec::new())?;
+    let mut inputs = [Input {
+        indent,
+        lines,
+        config: EditorConfig::default(),
+        encoding: encoding_rs::UTF_8,
+        bom: false,
+    }];
+    group_lines(opts, &mut inputs);
+    let lines = &inputs[0].lines;
+    Ok(lines.len() > 1 && lines[0].group != lines[lines.len() - 1].group)
+}
@@ -0,0 +1,165 @@
+use {
+    crate::{number_value, string_serde, AlignConfig, AlignError, Input, Line},
+    serde::{Deserialize, Deserializer, Serialize, Serializer},
+    std::{
+        cmp::Ordering,
+        fmt::{self, Display, Formatter},
+        num::NonZeroUsize,
+    },
+};
+
+/// A column by which rows are sorted.
+#[derive(Copy, Clone, Debug)]
+pub struct SortKey {
+    /// The column, starting at 1.
+    pub column: NonZeroUsize,
+    /// Whether the cells are compared as numbers instead of as text.
+    pub numeric: bool,
+    pub descending: bool,
+}
+
+impl Display for SortKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.column)?;
+        if self.numeric {
+            f.write_str(":num")?;
+        }
+        if self.descending {
+            f.write_str(":desc")?;
+        }
+        Ok(())
+    }
+}
+
+string_serde!(SortKey, parse_sort_key);
+
+/// Parses a sort key such as `1`, `3:num`, or `2:str:desc`.
+pub fn parse_sort_key(s: &str) -> Result<SortKey, AlignError> {
+    let invalid =
+        || AlignError::Value(format!("Expected `column[:type][:direction]`, found `{s}`"));
+    let mut parts = s.split(':');
+    let mut key = SortKey {
+        column: parts.next().unwrap().parse().map_err(|_| invalid())?,
+        numeric: false,
+        descending: false,
+    };
+    for part in parts {
+        match part {
+            "str" => key.numeric = false,
+            "num" => key.numeric = true,
+            "asc" => key.descending = false,
+            "desc" => key.descending = true,
+            _ => return Err(invalid()),
+        }
+    }
+    Ok(key)
+}
+
+/// Compares two cells by the key. Under numeric keys, numbers come before other cells in
+/// either direction.
+fn compare(opts: &AlignConfig, key: SortKey, a: &str, b: &str) -> Ordering {
+    let direction = |ordering: Ordering| match key.descending {
+        true => ordering.reverse(),
+        false => ordering,
+    };
+    if !key.numeric {
+        return direction(a.cmp(b));
+    }
+    let decimal = opts.decimal();
+    match (number_value(a, decimal), number_value(b, decimal)) {
+        (Some(a), Some(b)) => direction(a.total_cmp(&b)),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => direction(a.cmp(b)),
+    }
+}
+
+/// Whether the line is a row that can be moved by sorting.
+fn is_sortable(line: &Line) -> bool {
+    !line.header && line.rule.is_none() && !line.words.verbatim && line.words.words.len() > 0
+}
+
+/// Returns the cell of the key column, or an empty cell if the row is too short.
+fn cell(line: &Line, key: SortKey) -> &str {
+    line.words
+        .iter()
+        .nth(key.column.get() - 1)
+        .unwrap_or_default()
+}
+
+/// Sorts the rows of each input by the keys. The sort is stable.
+///
+/// Only consecutive rows of the same group are sorted, so headers, rules, blank lines,
+/// and lines printed as is stay in place.
+pub fn sort(opts: &AlignConfig, inputs: &mut [Input]) {
+    for input in inputs {
+        let lines = &mut input.lines;
+        let mut start = 0;
+        while start < lines.len() {
+            let first = &lines[start];
+            let len = lines[start..]
+                .iter()
+                .take_while(|l| is_sortable(l) && l.group == first.group)
+                .count();
+            if len == 0 {
+                start += 1;
+                continue;
+            }
+            lines[start..start + len].sort_by(|a, b| {
+                let mut ordering = Ordering::Equal;
+                for &key in &opts.sort_by {
+                    ordering =
+                        ordering.then_with(|| compare(opts, key, cell(a, key), cell(b, key)));
+                }
+                ordering
+            });
+            start += len;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::align};
+
+    #[test]
+    fn parse() {
+        let key = parse_sort_key("3:num:desc").unwrap();
+        assert_eq!(key.column.get(), 3);
+        assert!(key.numeric && key.descending);
+        assert_eq!(key.to_string(), "3:num:desc");
+        let key = parse_sort_key("2:str:asc").unwrap();
+        assert!(!key.numeric && !key.descending);
+        assert_eq!(key.to_string(), "2");
+        for invalid in ["", "0", "x", "1:number", "1:num:"] {
+            assert!(parse_sort_key(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn numbers_first() {
+        let opts = AlignConfig::default();
+        let desc = parse_sort_key("1:num:desc").unwrap();
+        assert_eq!(compare(&opts, desc, "9", "10"), Ordering::Greater);
+        assert_eq!(compare(&opts, desc, "9", "n/a"), Ordering::Less);
+        let asc = parse_sort_key("1:num").unwrap();
+        assert_eq!(compare(&opts, asc, "n/a", "9"), Ordering::Greater);
+        let text = parse_sort_key("1").unwrap();
+        assert_eq!(compare(&opts, text, "9", "10"), Ordering::Greater);
+    }
+
+    #[test]
+    fn keys() {
+        let config = AlignConfig {
+            header: true,
+            sort_by: vec![
+                parse_sort_key("1").unwrap(),
+                parse_sort_key("2:num:desc").unwrap(),
+            ],
+            ..AlignConfig::default()
+        };
+        let text = "k v\nb 1\na 2\nb 10\n\na 1\n";
+        let sorted = "k v\na 2\nb 10\nb 1\n\na 1\n";
+        assert_eq!(align(&config, text).unwrap(), sorted);
+    }
+}
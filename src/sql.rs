@@ -0,0 +1,261 @@
+use crate::{is_indent, mapped::LineText, trim, Words};
+
+#[derive(Default)]
+enum State {
+    #[default]
+    Outside,
+    /// Inside the column definitions of a `CREATE TABLE` statement.
+    Create,
+    /// Inside the tuples of an `INSERT ... VALUES` statement.
+    Insert,
+}
+
+/// Tokenizes the lines of an SQL script.
+#[derive(Default)]
+pub struct Sql {
+    /// The number of statements seen so far.
+    statement: usize,
+    state: State,
+    /// The number of unclosed parentheses of a `CREATE TABLE` statement.
+    depth: usize,
+    /// Whether the previous line was a complete `INSERT` statement. Runs of such lines are
+    /// aligned together.
+    single_insert: bool,
+}
+
+/// Keywords that start the table constraints of a `CREATE TABLE` statement.
+const CONSTRAINTS: [&[u8]; 7] = [
+    b"CONSTRAINT",
+    b"PRIMARY",
+    b"FOREIGN",
+    b"UNIQUE",
+    b"CHECK",
+    b"INDEX",
+    b"KEY",
+];
+
+impl Sql {
+    /// Tokenizes the next line and returns it together with the id of its statement.
+    ///
+    /// Column definitions are split into the name, the type, and the constraints. Tuples
+    /// are split into their values. All other lines are printed as is.
+    pub fn parse(&mut self, line: LineText) -> (Words, usize) {
+        let bytes = line.as_bytes();
+        let (start, end) = trim(bytes, 0, bytes.len());
+        if start == end {
+            return (Words::with_words(line, Vec::new()), self.statement);
+        }
+        let upper = bytes[start..end].to_ascii_uppercase();
+        let single_insert = std::mem::take(&mut self.single_insert);
+        if upper.starts_with(b"--") {
+            return (Words::verbatim(line), self.statement);
+        }
+        let words = match self.state {
+            State::Outside => {
+                if upper.starts_with(b"CREATE ") && find(&upper, b"TABLE").is_some() {
+                    self.statement += 1;
+                    self.depth = 0;
+                    self.track_depth(&bytes[start..end]);
+                    if self.depth > 0 {
+                        self.state = State::Create;
+                    }
+                    None
+                } else if upper.starts_with(b"INSERT ") {
+                    self.single_insert = upper.ends_with(b";");
+                    if !self.single_insert {
+                        self.state = State::Insert;
+                    }
+                    if !(single_insert && self.single_insert) {
+                        self.statement += 1;
+                    }
+                    find(&upper, b"VALUES").and_then(|values| {
+                        let values = start + values + b"VALUES".len();
+                        let (tuple, _) = trim(bytes, values, end);
+                        let mut words = vec![(start, values)];
+                        if bytes.get(tuple) != Some(&b'(') {
+                            return None;
+                        }
+                        words.extend(split_tuple(bytes, tuple, end));
+                        Some(words)
+                    })
+                } else {
+                    None
+                }
+            }
+            State::Create => {
+                let definition = !upper.starts_with(b")")
+                    && !CONSTRAINTS.iter().any(|k| {
+                        upper.starts_with(k)
+                            && upper
+                                .get(k.len())
+                                .is_none_or(|&c| !c.is_ascii_alphanumeric())
+                    });
+                self.track_depth(&bytes[start..end]);
+                if self.depth == 0 {
+                    self.state = State::Outside;
+                }
+                definition.then(|| split_definition(bytes, start, end))
+            }
+            State::Insert => {
+                if upper.ends_with(b";") {
+                    self.state = State::Outside;
+                }
+                (bytes[start] == b'(').then(|| split_tuple(bytes, start, end))
+            }
+        };
+        let Some(words) = words else {
+            return (Words::verbatim(line), self.statement);
+        };
+        let mut words = Words::with_words(line, words);
+        words.own_indent = true;
+        (words, self.statement)
+    }
+
+    fn track_depth(&mut self, s: &[u8]) {
+        let mut quote = None;
+        for &c in s {
+            match (quote, c) {
+                (Some(q), _) if c == q => quote = None,
+                (Some(_), _) => {}
+                (None, b'\'' | b'"' | b'`') => quote = Some(c),
+                (None, b'(') => self.depth += 1,
+                (None, b')') => self.depth = self.depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// Returns the end of the token starting at `pos`, which ends at the first space or tab
+/// outside of quotes and parentheses.
+fn token_end(line: &[u8], mut pos: usize, end: usize) -> usize {
+    let mut quote = None;
+    let mut depth = 0usize;
+    while pos < end {
+        let c = line[pos];
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, b'\'' | b'"' | b'`') => quote = Some(c),
+            (None, b'(') => depth += 1,
+            (None, b')') => depth = depth.saturating_sub(1),
+            (None, _) if depth == 0 && is_indent(c) => break,
+            _ => {}
+        }
+        pos += 1;
+    }
+    pos
+}
+
+/// Splits a column definition into the name, the type, and the constraints.
+fn split_definition(line: &[u8], start: usize, end: usize) -> Vec<(usize, usize)> {
+    let mut words = Vec::new();
+    let name_end = token_end(line, start, end);
+    words.push((start, name_end));
+    let (ty, _) = trim(line, name_end, end);
+    if ty < end {
+        let ty_end = token_end(line, ty, end);
+        words.push((ty, ty_end));
+        let (rest, _) = trim(line, ty_end, end);
+        if rest < end {
+            words.push((rest, end));
+        }
+    }
+    words
+}
+
+/// Splits a tuple into its values. The commas stay attached to the preceding values.
+fn split_tuple(line: &[u8], start: usize, end: usize) -> Vec<(usize, usize)> {
+    let mut words = Vec::new();
+    let mut quote = None;
+    let mut depth = 0usize;
+    let mut cell = start;
+    for pos in start..end {
+        let c = line[pos];
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, b'\'' | b'"' | b'`') => quote = Some(c),
+            (None, b'(') => depth += 1,
+            (None, b')') => depth = depth.saturating_sub(1),
+            (None, b',') if depth == 1 => {
+                words.push((cell, pos + 1));
+                (cell, _) = trim(line, pos + 1, end);
+            }
+            _ => {}
+        }
+    }
+    if cell < end {
+        words.push((cell, end));
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses the lines and returns the cells of each line, `None` for lines printed as
+    /// is, and the statement of each line.
+    fn parse(lines: &[&str]) -> Vec<(Option<Vec<String>>, usize)> {
+        let mut sql = Sql::default();
+        let mut res = Vec::new();
+        for line in lines {
+            let (words, statement) = sql.parse(line.to_string().into());
+            let cells = (!words.verbatim).then(|| words.iter().map(String::from).collect());
+            res.push((cells, statement));
+        }
+        res
+    }
+
+    fn cells(cells: &[&str]) -> Option<Vec<String>> {
+        Some(cells.iter().map(|c| c.to_string()).collect())
+    }
+
+    #[test]
+    fn create_table() {
+        let lines = parse(&[
+            "CREATE TABLE t (",
+            "  id integer PRIMARY KEY,",
+            "  price numeric(10, 2) NOT NULL DEFAULT 0,",
+            "  name 'a b',",
+            "  PRIMARY KEY (id)",
+            ");",
+        ]);
+        assert_eq!(lines[0], (None, 1));
+        assert_eq!(lines[1], (cells(&["id", "integer", "PRIMARY KEY,"]), 1));
+        assert_eq!(
+            lines[2],
+            (
+                cells(&["price", "numeric(10, 2)", "NOT NULL DEFAULT 0,"]),
+                1
+            )
+        );
+        assert_eq!(lines[3], (cells(&["name", "'a b',"]), 1));
+        assert_eq!(lines[4], (None, 1));
+        assert_eq!(lines[5], (None, 1));
+    }
+
+    #[test]
+    fn insert() {
+        let lines = parse(&[
+            "INSERT INTO t VALUES",
+            "  (1, 'a, b', f(1, 2)),",
+            "  (22, 'c', NULL);",
+            "-- single inserts",
+            "insert into t values (1, 'x');",
+            "insert into t values (2, 'y');",
+        ]);
+        assert_eq!(lines[0], (None, 1));
+        assert_eq!(lines[1], (cells(&["(1,", "'a, b',", "f(1, 2)),"]), 1));
+        assert_eq!(lines[2], (cells(&["(22,", "'c',", "NULL);"]), 1));
+        assert_eq!(lines[3], (None, 1));
+        let single = cells(&["insert into t values", "(1,", "'x');"]);
+        assert_eq!(lines[4], (single, 2));
+        assert_eq!(lines[5].1, 2);
+    }
+}
@@ -0,0 +1,44 @@
+use {
+    crate::Positioning,
+    anyhow::{Context, Error},
+    serde::{Deserialize, Serialize},
+    std::{fs, io::ErrorKind, path::Path},
+};
+
+/// The column widths saved by `--save-widths`.
+#[derive(Default, Serialize, Deserialize)]
+struct State {
+    widths: Vec<usize>,
+}
+
+/// Loads the column widths saved by `--save-widths`.
+///
+/// A missing file contains no widths, so the same file can be used for loading and saving
+/// from the first run on.
+pub fn load_widths(path: &Path) -> Result<Vec<usize>, Error> {
+    let context = || format!("Could not load the widths from {}", path.display());
+    let json = match fs::read(path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(Error::from(e).context(context())),
+    };
+    let state: State = serde_json::from_slice(&json).with_context(context)?;
+    Ok(state.widths)
+}
+
+/// Saves the widest width of each column of the groups.
+pub fn save_widths(path: &Path, groups: &[Positioning]) -> Result<(), Error> {
+    let mut state = State::default();
+    for positioning in groups {
+        let widths = positioning.max_width.as_slice();
+        if state.widths.len() < widths.len() {
+            state.widths.resize(widths.len(), 0);
+        }
+        for (max, &width) in state.widths.iter_mut().zip(widths) {
+            *max = (*max).max(width);
+        }
+    }
+    let json = serde_json::to_vec(&state)?;
+    fs::write(path, json)
+        .with_context(|| format!("Could not save the widths to {}", path.display()))
+}
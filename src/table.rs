@@ -0,0 +1,152 @@
+use crate::{trim, Input, Line, Positioning, Rule, Words};
+
+/// Returns whether `c` separates the cells of a drawn table.
+fn is_vertical(c: char) -> bool {
+    matches!(c, '|' | '│' | '┃' | '║')
+}
+
+/// Returns whether `c` is part of a horizontal border.
+fn is_horizontal(c: char) -> bool {
+    matches!(c, '-' | '=') || ('\u{2500}'..='\u{257f}').contains(&c)
+}
+
+/// Returns whether `c` can start or end a border, as the corners of `+----+` or
+/// `├────┤` do.
+fn is_edge(c: char) -> bool {
+    c == '+' || is_vertical(c) || (is_horizontal(c) && !matches!(c, '-' | '=' | '─' | '━' | '═'))
+}
+
+/// Returns whether the line is a border of a drawn table, such as `+----+----+` or
+/// `├────┼────┤`, or the rule below the header of psql, such as `----+----`.
+///
+/// Borders consist of nothing but the characters of the frame, so rows whose cells
+/// contain only dashes are not borders.
+pub fn is_border(line: &str) -> bool {
+    let line = line.trim_matches([' ', '\t']);
+    let (Some(first), Some(last)) = (line.chars().next(), line.chars().next_back()) else {
+        return false;
+    };
+    let frame = line.chars().any(is_horizontal)
+        && line
+            .chars()
+            .all(|c| c == '+' || is_vertical(c) || is_horizontal(c));
+    let psql = line.contains('+') && line.chars().all(|c| c == '+' || c == '-');
+    frame && (is_edge(first) && is_edge(last) || psql)
+}
+
+/// Splits a row of a drawn table at the vertical bars. The outer borders are removed.
+///
+/// Returns `None` if the line contains no vertical bar.
+pub fn split_row(line: &[u8]) -> Option<Vec<(usize, usize)>> {
+    let text = std::str::from_utf8(line).ok()?;
+    let mut bars: Vec<_> = text
+        .char_indices()
+        .filter(|&(_, c)| is_vertical(c))
+        .map(|(i, c)| (i, i + c.len_utf8()))
+        .collect();
+    if bars.is_empty() {
+        return None;
+    }
+    let (mut start, mut end) = trim(line, 0, line.len());
+    if bars[0].0 == start {
+        start = bars.remove(0).1;
+    }
+    if bars.last().is_some_and(|&(_, e)| e == end) {
+        end = bars.pop().unwrap().0;
+    }
+    let mut words = Vec::new();
+    let mut pos = start;
+    for (bar_start, bar_end) in bars {
+        words.push(trim(line, pos, bar_start));
+        pos = bar_end;
+    }
+    words.push(trim(line, pos, end));
+    Some(words)
+}
+
+/// Redraws the borders at the column widths of the rows below them, or above them for
+/// borders below the last row.
+pub fn draw_borders(inputs: &mut [Input], groups: &[Positioning]) {
+    for input in inputs {
+        let indent = String::from_utf8_lossy(input.indent.as_deref().unwrap_or_default());
+        let indent = indent.into_owned();
+        for i in 0..input.lines.len() {
+            if input.lines[i].rule != Some(Rule::Border) {
+                continue;
+            }
+            let is_row = |l: &&Line| l.rule.is_none() && l.words.words.len() > 0;
+            let below = input.lines[i..].iter().find(is_row);
+            let Some(group) = below
+                .or(input.lines[..i].iter().rfind(is_row))
+                .map(|l| l.group)
+            else {
+                continue;
+            };
+            let columns = input
+                .lines
+                .iter()
+                .filter(|l| l.group == group && l.rule.is_none())
+                .map(|l| l.words.words.len())
+                .max()
+                .unwrap_or(0);
+            let fill = match input.lines[i].words.line.contains('=') {
+                true => "=",
+                false => "-",
+            };
+            let mut border = format!("{indent}+");
+            for column in 0..columns {
+                border.push_str(&fill.repeat(groups[group].max_width.get(column) + 2));
+                border.push('+');
+            }
+            input.lines[i].words = Words::verbatim(border);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::AlignConfig};
+
+    #[test]
+    fn borders() {
+        assert!(is_border("+----+-----+"));
+        assert!(is_border("  |====|====|"));
+        assert!(is_border("├────┼────┤"));
+        assert!(is_border("----+------"));
+        assert!(!is_border("| - | - |"));
+        assert!(!is_border("-- | --"));
+        assert!(!is_border("| a | - |"));
+        assert!(!is_border(""));
+    }
+
+    #[test]
+    fn split() {
+        let line = b"| a  |  bb | c |";
+        let words = split_row(line).unwrap();
+        let cells: Vec<_> = words.iter().map(|&(s, e)| &line[s..e]).collect();
+        assert_eq!(cells, [&b"a"[..], b"bb", b"c"]);
+        let line = " 1 │ 22 ".as_bytes();
+        let words = split_row(line).unwrap();
+        let cells: Vec<_> = words.iter().map(|&(s, e)| &line[s..e]).collect();
+        assert_eq!(cells, [&b"1"[..], b"22"]);
+        assert!(split_row(b"(2 rows)").is_none());
+    }
+
+    #[test]
+    fn redraw() {
+        let config = AlignConfig {
+            table: true,
+            ..AlignConfig::default()
+        };
+        let text = "+--+--+\n| a | bb |\n+==+==+\n| ccc | d |\n| - | - |\n+--+--+\n";
+        let aligned = "\
++-----+----+
+| a   | bb |
++=====+====+
+| ccc | d  |
+| -   | -  |
++-----+----+
+";
+        assert_eq!(crate::align(&config, text).unwrap(), aligned);
+    }
+}
@@ -0,0 +1,181 @@
+use {
+    crate::{string_serde, AlignConfig, AlignError, Alignment, Positioning},
+    serde::{Deserialize, Deserializer, Serialize, Serializer},
+    std::fmt::{self, Display, Formatter},
+};
+
+#[derive(Clone, Debug)]
+enum Part {
+    Text(String),
+    Cell {
+        /// The column, starting at 0.
+        column: usize,
+        align: Option<Alignment>,
+        width: Option<usize>,
+    },
+}
+
+/// A format string into which the cells of each row are substituted.
+#[derive(Clone, Debug)]
+pub struct Template {
+    source: String,
+    parts: Vec<Part>,
+}
+
+impl Display for Template {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+string_serde!(Template, parse_template);
+
+/// Parses a template such as `{1} -> {3:>8}`.
+pub fn parse_template(s: &str) -> Result<Template, AlignError> {
+    let invalid = |msg: &str| AlignError::Value(format!("Invalid template `{s}`: {msg}"));
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.as_str().starts_with('{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.as_str().starts_with('}') => {
+                chars.next();
+                text.push('}');
+            }
+            '}' => return Err(invalid("unmatched `}`")),
+            '{' => {
+                let rest = chars.as_str();
+                let end = rest.find('}').ok_or_else(|| invalid("unclosed `{`"))?;
+                let (column, spec) = rest[..end].split_once(':').unwrap_or((&rest[..end], ""));
+                let column: usize = match column.parse() {
+                    Ok(0) | Err(_) => return Err(invalid("expected a column starting at 1")),
+                    Ok(n) => n,
+                };
+                let mut spec = spec.chars();
+                let align = match spec.clone().next() {
+                    Some('<') => Some(Alignment::Left),
+                    Some('>') => Some(Alignment::Right),
+                    Some('^') => Some(Alignment::Centered),
+                    _ => None,
+                };
+                if align.is_some() {
+                    spec.next();
+                }
+                let width = match spec.as_str() {
+                    "" => None,
+                    w => Some(w.parse().map_err(|_| invalid("expected a width"))?),
+                };
+                if !text.is_empty() {
+                    parts.push(Part::Text(std::mem::take(&mut text)));
+                }
+                parts.push(Part::Cell {
+                    column: column - 1,
+                    align: align.or(width.map(|_| Alignment::Left)),
+                    width,
+                });
+                chars = rest[end + 1..].chars();
+            }
+            c => text.push(c),
+        }
+    }
+    if !text.is_empty() {
+        parts.push(Part::Text(text));
+    }
+    Ok(Template {
+        source: s.to_string(),
+        parts,
+    })
+}
+
+impl Template {
+    /// Substitutes the cells into the template.
+    ///
+    /// Cells with an alignment are padded to the given width or, without one, to the
+    /// width of their column.
+    pub fn render(&self, opts: &AlignConfig, positioning: &Positioning, cells: &[&str]) -> String {
+        let mut res = String::new();
+        for part in self.parts.iter() {
+            let (column, align, width) = match *part {
+                Part::Text(ref text) => {
+                    res.push_str(text);
+                    continue;
+                }
+                Part::Cell {
+                    column,
+                    align,
+                    width,
+                } => (column, align, width),
+            };
+            let cell = cells.get(column).copied().unwrap_or_default();
+            let Some(align) = align else {
+                res.push_str(cell);
+                continue;
+            };
+            let width = width.unwrap_or_else(|| positioning.max_width.get(column));
+            let pad = width.saturating_sub(opts.width(cell));
+            let (left, right) = match align {
+                Alignment::Right => (pad, 0),
+                Alignment::Centered => (pad / 2, pad - pad / 2),
+                _ => (0, pad),
+            };
+            res.push_str(&" ".repeat(left));
+            res.push_str(cell);
+            res.push_str(&" ".repeat(right));
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let template = parse_template("{{{1}}} -> {3:>8}").unwrap();
+        assert_eq!(template.to_string(), "{{{1}}} -> {3:>8}");
+        assert!(matches!(
+            template.parts[..],
+            [
+                Part::Text(ref open),
+                Part::Cell {
+                    column: 0,
+                    align: None,
+                    width: None
+                },
+                Part::Text(ref arrow),
+                Part::Cell {
+                    column: 2,
+                    align: Some(Alignment::Right),
+                    width: Some(8)
+                },
+            ] if open == "{" && arrow == "} -> "
+        ));
+        let width = parse_template("{2:4}").unwrap();
+        assert!(matches!(
+            width.parts[..],
+            [Part::Cell {
+                align: Some(Alignment::Left),
+                width: Some(4),
+                ..
+            }]
+        ));
+        for invalid in ["{0}", "{x}", "{1", "a}", "{1:>x}"] {
+            assert!(parse_template(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn render() {
+        let config = AlignConfig {
+            template: Some(parse_template("{2:^} = {1:>5};{3}").unwrap()),
+            ..AlignConfig::default()
+        };
+        let text = crate::align(&config, "a bb\nccc d e\n").unwrap();
+        assert_eq!(text, "bb =     a;\nd  =   ccc;e\n");
+    }
+}
@@ -0,0 +1,122 @@
+use {
+    crate::Input,
+    serde::{Deserialize, Serialize},
+    std::time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The output format of timestamps.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+pub enum TimestampFormat {
+    /// Keep timestamps as they are.
+    Keep,
+    /// Convert timestamps to ISO 8601, e.g. `2024-01-02T10:00:00`. Syslog timestamps are
+    /// assumed to be from the current year.
+    Iso,
+}
+
+const MONTHS: [&[u8]; 12] = [
+    b"Jan", b"Feb", b"Mar", b"Apr", b"May", b"Jun", b"Jul", b"Aug", b"Sep", b"Oct", b"Nov", b"Dec",
+];
+
+fn is_digits(s: &[u8], min: usize, max: usize) -> bool {
+    s.len() >= min && s.len() <= max && s.iter().all(|c| c.is_ascii_digit())
+}
+
+/// Returns whether `s` is a date like `2024-01-02`.
+fn is_date(s: &[u8]) -> bool {
+    s.len() == 10
+        && is_digits(&s[..4], 4, 4)
+        && s[4] == b'-'
+        && is_digits(&s[5..7], 2, 2)
+        && s[7] == b'-'
+        && is_digits(&s[8..], 2, 2)
+}
+
+/// Returns whether `s` is a time like `10:00:00`, optionally followed by fractional seconds
+/// and a time zone.
+fn is_time(s: &[u8]) -> bool {
+    s.len() >= 8
+        && is_digits(&s[..2], 2, 2)
+        && s[2] == b':'
+        && is_digits(&s[3..5], 2, 2)
+        && s[5] == b':'
+        && is_digits(&s[6..8], 2, 2)
+        && s[8..]
+            .iter()
+            .all(|&c| c.is_ascii_digit() || matches!(c, b'.' | b',' | b'Z' | b'+' | b'-' | b':'))
+}
+
+/// Returns whether `s` is a date, optionally followed by a time after a `T` or a space.
+pub fn is_date_time(s: &str) -> bool {
+    let s = s.as_bytes();
+    match s.get(10) {
+        None => is_date(s),
+        Some(b'T' | b' ') => is_date(&s[..10]) && is_time(&s[11..]),
+        Some(_) => false,
+    }
+}
+
+/// Returns the number of words forming a timestamp at the start of `words`.
+fn timestamp_len(line: &[u8], words: &[(usize, usize)]) -> usize {
+    let word = |i: usize| words.get(i).map(|&(start, end)| &line[start..end]);
+    match (word(0), word(1), word(2)) {
+        (Some(m), Some(d), Some(t)) if MONTHS.contains(&m) && is_digits(d, 1, 2) && is_time(t) => 3,
+        (Some(d), Some(t), _) if is_date(d) && is_time(t) => 2,
+        _ => 1,
+    }
+}
+
+/// Merges the words of syslog timestamps like `Jan  2 10:00:00` and of ISO 8601 timestamps
+/// like `2024-01-02 10:00:00` into single words.
+pub fn merge(line: &[u8], words: &mut Vec<(usize, usize)>) {
+    let mut i = 0;
+    while i < words.len() {
+        let len = timestamp_len(line, &words[i..]);
+        if len > 1 {
+            words[i].1 = words[i + len - 1].1;
+            words.drain(i + 1..i + len);
+        }
+        i += 1;
+    }
+}
+
+/// Converts a merged timestamp to ISO 8601.
+fn to_iso(s: &str, year: u64) -> Option<String> {
+    let parts: Vec<&str> = s.split_ascii_whitespace().collect();
+    match parts[..] {
+        [date, time] if is_date(date.as_bytes()) => Some(format!("{date}T{time}")),
+        [month, day, time] if is_time(time.as_bytes()) => {
+            let month = MONTHS.iter().position(|m| *m == month.as_bytes())? + 1;
+            let day: u8 = day.parse().ok()?;
+            Some(format!("{year}-{month:02}-{day:02}T{time}"))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the current year in UTC.
+fn current_year() -> u64 {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    // https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = secs / 86400 + 719468;
+    let era = z / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    yoe + era * 400 + (month <= 2) as u64
+}
+
+/// Converts all merged timestamps to ISO 8601.
+pub fn reformat(inputs: &mut [Input]) {
+    let year = current_year();
+    for line in inputs.iter_mut().flat_map(|i| &mut i.lines) {
+        line.words.map(|_, word| to_iso(word, year));
+    }
+}
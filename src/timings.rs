@@ -0,0 +1,47 @@
+use std::{
+    fs,
+    time::{Duration, Instant},
+};
+
+/// The time spent in each phase of a run, printed by `--timings`.
+pub struct Timings {
+    phases: Vec<(&'static str, Duration)>,
+    /// The end of the last phase.
+    last: Instant,
+}
+
+impl Timings {
+    pub fn new() -> Self {
+        Timings {
+            phases: Vec::new(),
+            last: Instant::now(),
+        }
+    }
+
+    /// Ends a phase that started at the end of the previous one.
+    pub fn lap(&mut self, phase: &'static str) {
+        let now = Instant::now();
+        self.phases.push((phase, now - self.last));
+        self.last = now;
+    }
+
+    /// Prints the phases and the peak memory usage to stderr.
+    pub fn report(&self) {
+        let total: Duration = self.phases.iter().map(|p| p.1).sum();
+        for (phase, duration) in self.phases.iter().chain([&("total", total)]) {
+            eprintln!("{phase:<12}{:>10.3} ms", duration.as_secs_f64() * 1000.0);
+        }
+        if let Some(kib) = peak_memory() {
+            eprintln!("{:<12}{:>10.1} MiB", "peak memory", kib as f64 / 1024.0);
+        }
+    }
+}
+
+/// Returns the peak resident set size in KiB.
+///
+/// This is only available on Linux.
+fn peak_memory() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find_map(|l| l.strip_prefix("VmHWM:"))?;
+    line.trim().strip_suffix("kB")?.trim().parse().ok()
+}
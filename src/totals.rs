@@ -0,0 +1,116 @@
+use {
+    crate::{number_value, AlignConfig, Input, Line, Rule, Words},
+    serde::{Deserialize, Serialize},
+};
+
+/// A statistic of the footer of `--totals`.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+pub enum Stat {
+    /// The sum of the numbers.
+    Sum,
+    /// The arithmetic mean of the numbers.
+    Mean,
+    /// The number of numbers.
+    Count,
+}
+
+impl Stat {
+    fn label(self) -> &'static str {
+        match self {
+            Stat::Sum => "Total",
+            Stat::Mean => "Mean",
+            Stat::Count => "Count",
+        }
+    }
+}
+
+#[derive(Default)]
+struct Column {
+    sum: f64,
+    count: usize,
+    /// The largest number of digits after the decimal separator.
+    precision: usize,
+}
+
+impl Column {
+    fn format(&self, stat: Stat, decimal: char) -> String {
+        let res = match stat {
+            Stat::Sum => format!("{:.*}", self.precision, self.sum),
+            Stat::Mean if self.count == 0 => return String::new(),
+            Stat::Mean => format!("{:.*}", self.precision.max(2), self.sum / self.count as f64),
+            Stat::Count => return self.count.to_string(),
+        };
+        res.replace('.', &decimal.to_string())
+    }
+}
+
+/// Appends a rule and a row per statistic with the totals of the columns to each input.
+///
+/// The cells of the rule are filled in by [`draw_rules`] once the column widths are known.
+pub fn append(opts: &AlignConfig, inputs: &mut [Input]) {
+    let Some(last) = opts.totals.iter().map(|c| c.get()).max() else {
+        return;
+    };
+    let decimal = opts.decimal();
+    for input in inputs {
+        if input.lines.iter().all(|l| l.words.verbatim) {
+            continue;
+        }
+        let mut columns: Vec<Column> = (0..last).map(|_| Column::default()).collect();
+        let rows = input
+            .lines
+            .iter()
+            .filter(|l| !l.header && !l.words.verbatim);
+        for line in rows {
+            for total in &opts.totals {
+                let i = total.get() - 1;
+                let Some(cell) = line.words.iter().nth(i) else {
+                    continue;
+                };
+                let Some(value) = number_value(cell, decimal) else {
+                    continue;
+                };
+                let column = &mut columns[i];
+                column.sum += value;
+                column.count += 1;
+                let precision = cell.split_once(decimal).map(|(_, f)| f.len());
+                column.precision = column.precision.max(precision.unwrap_or(0));
+            }
+        }
+        let group = input.lines.last().map(|l| l.group).unwrap_or(0);
+        let line = |cells: Vec<String>, rule| Line {
+            words: Words::from_cells(&cells),
+            group,
+            header: false,
+            rule,
+        };
+        input
+            .lines
+            .push(line(vec!["-".to_string(); last], Some(Rule::Totals)));
+        for &stat in &opts.total_stats {
+            let mut cells = vec![String::new(); last];
+            for total in &opts.totals {
+                let i = total.get() - 1;
+                cells[i] = columns[i].format(stat, decimal);
+            }
+            if cells[0].is_empty() {
+                cells[0] = stat.label().to_string();
+            }
+            input.lines.push(line(cells, None));
+        }
+    }
+}
+
+/// Fills the rules with dashes as wide as the columns.
+pub fn draw_rules(inputs: &mut [Input], widths: impl Fn(&Line, usize) -> usize) {
+    for line in inputs.iter_mut().flat_map(|i| &mut i.lines) {
+        if line.rule == Some(Rule::Totals) {
+            let dashes: Vec<String> = (0..line.words.words.len())
+                .map(|i| "-".repeat(widths(line, i)))
+                .collect();
+            line.words.map(|i, _| Some(dashes[i].clone()));
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use {
+    crate::{parse_positioning, AlignConfig},
+    wasm_bindgen::prelude::*,
+};
+
+/// Aligns the columns of `text`.
+///
+/// `spec` is a positioning spec such as `<><`. `options` is an optional object with the
+/// remaining options, named like the command line arguments, e.g. `{ "field-sep": "," }`.
+#[wasm_bindgen(js_name = align)]
+pub fn align_js(text: &str, spec: &str, options: JsValue) -> Result<String, JsError> {
+    let mut config: AlignConfig = match options.is_undefined() || options.is_null() {
+        true => AlignConfig::default(),
+        false => serde_wasm_bindgen::from_value(options)?,
+    };
+    config.positioning = parse_positioning(spec).map_err(|e| JsError::new(&format!("{e}")))?;
+    crate::align(&config, text).map_err(|e| JsError::new(&format!("{:#}", anyhow::Error::from(e))))
+}
@@ -0,0 +1,44 @@
+use {
+    serde::{Deserialize, Serialize},
+    std::fmt,
+    unicode_width::UnicodeWidthStr,
+};
+
+/// Measures the display width of text.
+pub trait WidthMeasure {
+    /// Returns the width of `s` in columns.
+    fn width(&self, s: &str) -> usize;
+}
+
+impl fmt::Debug for dyn WidthMeasure + Send + Sync {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("WidthMeasure")
+    }
+}
+
+/// The built-in width measures.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+pub enum Measure {
+    /// The display width in a terminal. East Asian wide characters are two columns wide.
+    Unicode,
+    /// Like `unicode`, but characters of ambiguous width are also two columns wide, as in
+    /// CJK contexts.
+    Cjk,
+    /// The number of bytes.
+    Bytes,
+    /// The number of characters.
+    Chars,
+}
+
+impl WidthMeasure for Measure {
+    fn width(&self, s: &str) -> usize {
+        match self {
+            Measure::Unicode => s.width(),
+            Measure::Cjk => s.width_cjk(),
+            Measure::Bytes => s.len(),
+            Measure::Chars => s.chars().count(),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use {
+    crate::{string_serde, AlignConfig, AlignError, Input},
+    serde::{Deserialize, Deserializer, Serialize, Serializer},
+    std::{
+        fmt::{self, Display, Formatter},
+        num::NonZeroUsize,
+    },
+};
+
+/// A column whose cells are wrapped at a width.
+#[derive(Copy, Clone, Debug)]
+pub struct ColumnWrap {
+    /// The column, starting at 1.
+    pub column: NonZeroUsize,
+    pub width: NonZeroUsize,
+}
+
+impl Display for ColumnWrap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.column, self.width)
+    }
+}
+
+string_serde!(ColumnWrap, parse_column_wrap);
+
+/// Parses a column and a width such as `4:60`.
+pub fn parse_column_wrap(s: &str) -> Result<ColumnWrap, AlignError> {
+    let invalid = || AlignError::Value(format!("Expected `column:width`, found `{s}`"));
+    let (column, width) = s.split_once(':').ok_or_else(invalid)?;
+    Ok(ColumnWrap {
+        column: column.parse().map_err(|_| invalid())?,
+        width: width.parse().map_err(|_| invalid())?,
+    })
+}
+
+/// Breaks `text` into lines no wider than `width` at whitespace. Words wider than `width`
+/// get lines of their own.
+fn wrap_text(opts: &AlignConfig, text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && opts.width(&line) + 1 + opts.width(word) > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    lines.push(line);
+    lines
+}
+
+/// Wraps the cells of the column. The following lines of each cell are printed below it
+/// in the same column.
+pub fn wrap(opts: &AlignConfig, wrap: ColumnWrap, inputs: &mut [Input]) {
+    let column = wrap.column.get() - 1;
+    let width = wrap.width.get();
+    for line in inputs.iter_mut().flat_map(|i| &mut i.lines) {
+        let words = &mut line.words;
+        if line.header || line.rule.is_some() || words.words.len() <= column {
+            continue;
+        }
+        let cell = words.iter().nth(column).unwrap_or_default();
+        let mut lines = wrap_text(opts, cell, width);
+        if let Some(more) = words.more.get(column) {
+            lines.extend(more.iter().flat_map(|l| wrap_text(opts, l, width)));
+        }
+        let first = lines.remove(0);
+        words.map(|i, _| (i == column).then(|| first.clone()));
+        if words.more.len() <= column {
+            words.more.resize(column + 1, Vec::new());
+        }
+        words.more[column] = lines;
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{mapped::LineText, trim, Words};
+
+/// Tokenizes the lines of a YAML document.
+#[derive(Default)]
+pub struct Yaml {
+    /// The key columns and ids of the enclosing mappings.
+    mappings: Vec<(usize, usize)>,
+    /// The number of mappings seen so far.
+    num_mappings: usize,
+    /// The key column of the block scalar whose content is being read.
+    block: Option<usize>,
+}
+
+impl Yaml {
+    /// Tokenizes the next line and returns it together with the id of its mapping.
+    ///
+    /// The key of a `key: value` line, including the indentation and the `:`, forms the
+    /// first cell and the value the second. All other lines are printed as is.
+    pub fn parse(&mut self, line: LineText) -> (Words, usize) {
+        let bytes = line.as_bytes();
+        let indent = bytes.iter().take_while(|&&c| c == b' ').count();
+        let blank = bytes[indent..].iter().all(|&c| c == b' ' || c == b'\t');
+        if let Some(block) = self.block {
+            if blank || indent > block {
+                return (Words::verbatim(line), 0);
+            }
+            self.block = None;
+        }
+        if blank {
+            return (Words::with_words(line, Vec::new()), 0);
+        }
+        let Some((key, colon)) = split_key(bytes, indent) else {
+            return (Words::verbatim(line), 0);
+        };
+        // A sequence entry starts a new mapping.
+        let entry = key > indent;
+        while let Some(&(k, _)) = self.mappings.last() {
+            if k < key || (k == key && !entry) {
+                break;
+            }
+            self.mappings.pop();
+        }
+        let mapping = match self.mappings.last() {
+            Some(&(k, id)) if k == key => id,
+            _ => {
+                let id = self.num_mappings;
+                self.num_mappings += 1;
+                self.mappings.push((key, id));
+                id
+            }
+        };
+        let (start, end) = trim(bytes, colon + 1, bytes.len());
+        if start == end {
+            return (Words::verbatim(line), mapping);
+        }
+        if bytes[start] == b'|' || bytes[start] == b'>' {
+            self.block = Some(key);
+        }
+        let mut words = Words::with_words(line, vec![(0, colon + 1), (start, end)]);
+        words.own_indent = true;
+        (words, mapping)
+    }
+}
+
+/// Returns the column of the key and the position of the `:` of a `key: value` line.
+fn split_key(line: &[u8], indent: usize) -> Option<(usize, usize)> {
+    let mut pos = indent;
+    while line[pos..].starts_with(b"- ") {
+        pos += 2;
+        pos += line[pos..].iter().take_while(|&&c| c == b' ').count();
+    }
+    let key = pos;
+    match line.get(pos)? {
+        b'#' | b'[' | b'{' | b'?' | b'|' | b'>' => return None,
+        &quote @ (b'"' | b'\'') => {
+            let mut esc = false;
+            pos += 1;
+            loop {
+                let c = *line.get(pos)?;
+                pos += 1;
+                if c == quote && !esc {
+                    break;
+                }
+                esc = quote == b'"' && !esc && c == b'\\';
+            }
+            pos += line[pos..].iter().take_while(|&&c| c == b' ').count();
+            if line.get(pos) != Some(&b':') {
+                return None;
+            }
+        }
+        _ => loop {
+            match *line.get(pos)? {
+                b':' if matches!(line.get(pos + 1), None | Some(b' ')) => break,
+                b'#' if line[pos - 1] == b' ' => return None,
+                _ => pos += 1,
+            }
+        },
+    }
+    if matches!(line.get(pos + 1), Some(c) if *c != b' ') {
+        return None;
+    }
+    Some((key, pos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys() {
+        assert_eq!(split_key(b"a: 1", 0), Some((0, 1)));
+        assert_eq!(split_key(b"  url: http://x", 2), Some((2, 5)));
+        assert_eq!(split_key(b"- - name: x", 0), Some((4, 8)));
+        assert_eq!(split_key(b"'a: b' : 1", 0), Some((0, 7)));
+        assert_eq!(split_key(br#""a\": b": 1"#, 0), Some((0, 8)));
+        assert_eq!(split_key(b"# a: b", 0), None);
+        assert_eq!(split_key(b"a #b: c", 0), None);
+        assert_eq!(split_key(b"a:b", 0), None);
+        assert_eq!(split_key(b"- item", 0), None);
+    }
+
+    /// Parses the lines and returns the cells and the mapping of each line.
+    fn parse(lines: &[&str]) -> Vec<(Vec<String>, usize)> {
+        let mut yaml = Yaml::default();
+        let mut res = Vec::new();
+        for line in lines {
+            let (words, mapping) = yaml.parse(line.to_string().into());
+            res.push((words.iter().map(String::from).collect(), mapping));
+        }
+        res
+    }
+
+    #[test]
+    fn mappings() {
+        let lines = parse(&[
+            "name: x",
+            "spec:",
+            "  replicas: 3",
+            "  script: |",
+            "    a: b",
+            "  image: y",
+            "items:",
+            "  - id: 1",
+            "    value: a",
+            "  - id: 2",
+        ]);
+        let cells = |cells: &[&str]| cells.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+        assert_eq!(lines[0], (cells(&["name:", "x"]), 0));
+        assert_eq!(lines[1], (cells(&[]), 0));
+        assert_eq!(lines[2], (cells(&["  replicas:", "3"]), 1));
+        assert_eq!(lines[3], (cells(&["  script:", "|"]), 1));
+        // The content of block scalars is printed as is.
+        assert_eq!(lines[4].0, cells(&[]));
+        assert_eq!(lines[5], (cells(&["  image:", "y"]), 1));
+        assert_eq!(lines[7], (cells(&["  - id:", "1"]), 2));
+        assert_eq!(lines[8], (cells(&["    value:", "a"]), 2));
+        // Each sequence entry is a mapping of its own.
+        assert_eq!(lines[9], (cells(&["  - id:", "2"]), 3));
+    }
+}